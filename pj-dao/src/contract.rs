@@ -1,29 +1,73 @@
 use crate::error::{ContractError, ContractResult};
-use crate::game::{locked_per_player, Game, GameDetails, GameStatus, Player, NUM_OF_DICES};
+use crate::game::{
+    locked_per_player, payout_msg, Game, GameDetails, GameResult, GameStatus, Invitation, Player,
+    RoomSummary, ScoringMode, ScoringTable, Secret, SecretHash, BASIS_POINTS, DEFAULT_NUM_OF_DICES,
+    DEFAULT_REROLL_ROUNDS, HOST, MAX_NUM_OF_DICES, MAX_PLAYERS, MAX_REROLL_ROUNDS,
+    MAX_SERIES_GAMES, MIN_NUM_OF_DICES, MIN_PLAYERS, MIN_SERIES_GAMES, SINGLE_GAME,
+};
+use crate::governance::{
+    vote_weight, Proposal, ProposalId, ProposalKind, ProposalStatus, VoteOption,
+};
+use crate::leaderboard::{LeaderboardEntry, LeaderboardKind};
+use crate::matchmaking::{MatchWindow, QueueEntry};
 use crate::msg::{
-    HandleMsg, InitMsg, JoinNftDetails, Metadata, NftHandleMsg, NftInitMsg, NftQueryAnswer,
-    NftQueryMsg, PostInitCallback, QueryMsg, QueryWithPermit,
+    default_accepted_denoms, default_xp_tiers, ensure_correct_accepted_denoms,
+    ensure_correct_xp_tiers, AcceptedDenom, DenomSolvency, Extension, HandleMsg, InitMsg,
+    JoinNftDetails, Metadata, NftHandleMsg, NftInitMsg, NftQueryAnswer, NftQueryMsg,
+    PostInitCallback, QueryMsg, QueryWithPermit, ReceiveMsg, RoomFilter, Snip20Token,
+    SolvencyResponse, Viewer, XpTier,
 };
+use crate::rating::{apply_rating_changes, xp_gain, Rating};
 use crate::state::{
-    load_admin, load_game, load_joiner, load_last_game_index, nft_address, nft_code_hash,
-    nft_code_id, remove_game, save_admin, save_game, save_joiner, save_last_game_index,
-    save_nft_address, save_nft_code_hash, save_nft_code_id,
+    load_accepted_denoms, load_admin, load_contract_address, load_entropy, load_escrow,
+    load_escrow_tokens, load_game, load_game_result, load_governance_config, load_joiner,
+    load_last_game_index, load_last_proposal_index, load_last_tournament_index, load_leaderboard,
+    load_leaderboard_denoms, load_leaderboard_opt_out, load_match_queue, load_nft_rating,
+    load_pending_game_expiration, load_player_active_games, load_player_games, load_player_rating,
+    load_player_stats, load_proposal, load_rake, load_scoring_table, load_status_games,
+    load_token_whitelist, load_tournament, load_treasury, load_treasury_tokens, load_turn_timeout,
+    load_vote, load_xp_scaling, load_xp_tiers, nft_address, nft_code_hash, nft_code_id,
+    remove_game, save_accepted_denoms, save_admin, save_contract_address, save_entropy,
+    save_escrow, save_escrow_tokens, save_game, save_game_result, save_governance_config,
+    save_joiner, save_last_game_index, save_last_proposal_index, save_last_tournament_index,
+    save_leaderboard, save_leaderboard_denoms, save_leaderboard_opt_out, save_match_queue,
+    save_nft_address, save_nft_code_hash, save_nft_code_id, save_nft_rating,
+    save_pending_game_expiration, save_player_game, save_player_rating, save_player_stats,
+    save_proposal, save_rake, save_scoring_table, save_token_whitelist, save_tournament,
+    save_treasury, save_treasury_tokens, save_turn_timeout, save_vote, save_xp_scaling,
+    save_xp_tiers, PREFIX_REVOKED_PERMITS,
+};
+use crate::tournament::{
+    ensure_correct_tournament, Tournament, TournamentDetails, TournamentId, TournamentMatch,
+    TournamentStatus,
 };
 use cosmwasm_std::{
-    has_coins, log, to_binary, Api, Binary, CanonicalAddr, Coin, CosmosMsg, Env, Extern,
-    HandleResponse, HumanAddr, InitResponse, Querier, QueryRequest, StdError, StdResult, Storage,
-    WasmMsg, WasmQuery,
+    coin, from_binary, has_coins, log, to_binary, Api, BankMsg, Binary, CanonicalAddr, Coin,
+    CosmosMsg, Env, Extern, HandleResponse, HumanAddr, InitResponse, Querier, QueryRequest,
+    StdError, StdResult, Storage, Uint128, WasmMsg, WasmQuery,
 };
-use secret_toolkit::permit::Permit;
+use secret_toolkit::permit::{validate, Permit};
 use secret_toolkit::serialization::{Json, Serde};
+use sha2::{Digest, Sha256};
 
 pub type GameId = u64;
 
-pub type Secret = u64;
-
 /// Initial game index
 pub const INIT_INDEX: GameId = 0;
 
+/// Default number of blocks, player has to make a move in
+pub const DEFAULT_TURN_TIMEOUT: u64 = 100;
+
+/// Min number of blocks, player has to make a move in, so the forfeit can not be claimed right away
+pub const MIN_TURN_TIMEOUT: u64 = 10;
+
+/// Default and max number of entries, returned by the paginated queries
+pub const DEFAULT_QUERY_LIMIT: u32 = 10;
+pub const MAX_QUERY_LIMIT: u32 = 50;
+
+/// Default rake in basis points, taken from the stake of each game
+pub const DEFAULT_RAKE: u16 = 0;
+
 pub fn init<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
@@ -33,6 +77,33 @@ pub fn init<S: Storage, A: Api, Q: Querier>(
     save_nft_code_hash(&mut deps.storage, msg.nft_code_hash)?;
     save_nft_code_id(&mut deps.storage, msg.nft_code_id)?;
     save_admin(&mut deps.storage, &env.message.sender)?;
+    let turn_timeout = msg.turn_timeout.unwrap_or(DEFAULT_TURN_TIMEOUT);
+    ensure_correct_turn_timeout(turn_timeout)?;
+    save_turn_timeout(&mut deps.storage, turn_timeout)?;
+    save_pending_game_expiration(&mut deps.storage, msg.pending_game_expiration)?;
+    save_scoring_table(&mut deps.storage, &msg.scoring_table.unwrap_or_default())?;
+
+    let xp_tiers = msg.xp_tiers.unwrap_or_else(default_xp_tiers);
+    ensure_correct_xp_tiers(&xp_tiers)?;
+    save_xp_tiers(&mut deps.storage, &xp_tiers)?;
+
+    let governance_config = msg.governance.unwrap_or_default();
+    governance_config.ensure_is_correct()?;
+    save_governance_config(&mut deps.storage, &governance_config)?;
+    save_last_proposal_index(&mut deps.storage, &INIT_INDEX)?;
+    save_last_tournament_index(&mut deps.storage, &INIT_INDEX)?;
+
+    let rake = msg.rake.unwrap_or(DEFAULT_RAKE);
+    ensure_correct_rake(rake)?;
+    save_rake(&mut deps.storage, rake)?;
+    save_treasury(&mut deps.storage, &[])?;
+    save_escrow(&mut deps.storage, &[])?;
+    save_contract_address(&mut deps.storage, &env.contract.address)?;
+    save_xp_scaling(&mut deps.storage, msg.xp_scaling.unwrap_or(false))?;
+
+    let accepted_denoms = msg.accepted_denoms.unwrap_or_else(default_accepted_denoms);
+    ensure_correct_accepted_denoms(&accepted_denoms)?;
+    save_accepted_denoms(&mut deps.storage, &accepted_denoms)?;
 
     Ok(InitResponse::default())
 }
@@ -49,15 +120,40 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
         HandleMsg::CreateNewGameRoom {
             nft_id,
             base_bet,
-            secret,
+            secret_hash,
             permit,
-        } => create_new_game_room(deps, env, nft_id, base_bet, secret, permit),
+            shielded,
+            max_players,
+            num_of_dices,
+            reroll_rounds,
+            invitees,
+            invite_code_hash,
+            scoring_mode,
+            best_of,
+        } => create_new_game_room(
+            deps,
+            env,
+            nft_id,
+            base_bet,
+            secret_hash,
+            permit,
+            shielded.unwrap_or(false),
+            max_players.unwrap_or(MIN_PLAYERS),
+            num_of_dices.unwrap_or(DEFAULT_NUM_OF_DICES),
+            reroll_rounds.unwrap_or(DEFAULT_REROLL_ROUNDS),
+            scoring_mode.unwrap_or_default(),
+            invitees.unwrap_or_default(),
+            invite_code_hash,
+            best_of.unwrap_or(SINGLE_GAME),
+        ),
         HandleMsg::JoinGame {
             nft_id,
             game_id,
-            secret,
+            secret_hash,
             permit,
-        } => join_game(deps, env, nft_id, game_id, secret, permit),
+            invite_code,
+        } => join_game(deps, env, nft_id, game_id, secret_hash, permit, invite_code),
+        HandleMsg::RevealSecret { game_id, secret } => reveal_secret(deps, env, game_id, secret),
         HandleMsg::Roll { game_id } => roll(deps, env, game_id),
         HandleMsg::ReRoll { game_id, dices } => reroll(deps, env, game_id, dices),
         HandleMsg::AdminMint {
@@ -65,6 +161,78 @@ pub fn handle<S: Storage, A: Api, Q: Querier>(
             private_metadata,
         } => admin_mint(deps, env, to, private_metadata),
         HandleMsg::EndGame { game_id } => end_game(deps, env, game_id),
+        HandleMsg::ClaimTimeout { game_id } => claim_timeout(deps, env, game_id),
+        HandleMsg::CancelGame { game_id } => cancel_game(deps, env, game_id),
+        HandleMsg::SetTurnTimeout { blocks } => set_turn_timeout(deps, env, blocks),
+        HandleMsg::SetPendingGameExpiration { blocks } => {
+            set_pending_game_expiration(deps, env, blocks)
+        }
+        HandleMsg::SetScoringTable { scoring_table } => set_scoring_table(deps, env, scoring_table),
+        HandleMsg::SetXpTiers { xp_tiers } => set_xp_tiers(deps, env, xp_tiers),
+        HandleMsg::SetRake { rake } => set_rake(deps, env, rake),
+        HandleMsg::TreasuryWithdraw { recipient, amount } => {
+            treasury_withdraw(deps, env, recipient, amount)
+        }
+        HandleMsg::SetXpScaling { enabled } => set_xp_scaling(deps, env, enabled),
+        HandleMsg::SetAcceptedDenoms { accepted_denoms } => {
+            set_accepted_denoms(deps, env, accepted_denoms)
+        }
+        HandleMsg::SetTokenWhitelist { tokens } => set_token_whitelist(deps, env, tokens),
+        HandleMsg::Receive {
+            from, amount, msg, ..
+        } => receive(deps, env, from, amount, msg),
+        HandleMsg::QueueForMatch {
+            nft_id,
+            base_bet,
+            secret_hash,
+            permit,
+            window,
+        } => queue_for_match(
+            deps,
+            env,
+            nft_id,
+            base_bet,
+            secret_hash,
+            permit,
+            window.unwrap_or_default(),
+        ),
+        HandleMsg::LeaveQueue { base_bet } => leave_queue(deps, env, base_bet),
+        HandleMsg::CreateTournament {
+            bracket_size,
+            entry_fee,
+            min_xp,
+            prize_split,
+        } => create_tournament(
+            deps,
+            env,
+            bracket_size,
+            entry_fee,
+            min_xp.unwrap_or_default(),
+            prize_split,
+        ),
+        HandleMsg::RegisterForTournament {
+            tournament_id,
+            nft_id,
+            secret_hash,
+            permit,
+        } => register_for_tournament(deps, env, tournament_id, nft_id, secret_hash, permit),
+        HandleMsg::UnregisterFromTournament { tournament_id } => {
+            unregister_from_tournament(deps, env, tournament_id)
+        }
+        HandleMsg::CancelTournament { tournament_id } => {
+            cancel_tournament(deps, env, tournament_id)
+        }
+        HandleMsg::SetLeaderboardOptOut { opt_out } => set_leaderboard_opt_out(deps, env, opt_out),
+        HandleMsg::SubmitProposal { description, kind } => {
+            submit_proposal(deps, env, description, kind)
+        }
+        HandleMsg::CastVote {
+            proposal_id,
+            nft_id,
+            permit,
+            vote,
+        } => cast_vote(deps, env, proposal_id, nft_id, permit, vote),
+        HandleMsg::ExecuteProposal { proposal_id } => execute_proposal(deps, env, proposal_id),
     }
 }
 
@@ -216,14 +384,29 @@ pub fn create_nft_contract<S: Storage, A: Api, Q: Querier>(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_new_game_room<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     nft_id: String,
     base_bet: Coin,
-    secret: Secret,
+    secret_hash: Binary,
     permit: Permit,
+    shielded: bool,
+    max_players: u8,
+    num_of_dices: u8,
+    reroll_rounds: u8,
+    scoring_mode: ScoringMode,
+    invitees: Vec<HumanAddr>,
+    invite_code_hash: Option<Binary>,
+    best_of: u8,
 ) -> ContractResult<HandleResponse> {
+    // Ensure commitment to the secret is a sha256 hash
+    let secret_hash = to_secret_hash(secret_hash)?;
+
+    // Ensure invite code hash is a sha256 hash as well
+    let invite_code_hash = invite_code_hash.map(to_secret_hash).transpose()?;
+
     // Ensure given account joined dao, retrieve it's nfts.
     let player_nfts = query_player_nfts(deps, &env.message.sender, permit)?;
 
@@ -231,21 +414,61 @@ pub fn create_new_game_room<S: Storage, A: Api, Q: Querier>(
     ensure_can_access_nft(player_nfts, &nft_id)?;
 
     // Ensure player can use given nft in a game
-    ensure_can_use_nft_in_a_game(deps, nft_id.clone(), &base_bet)?;
-
-    // check whether nft supports base_bet
+    let host_xp = ensure_can_use_nft_in_a_game(deps, nft_id.clone(), &base_bet, shielded)?;
 
     // ensure base bet is greater then zero
     ensure_correct_base_bet(&base_bet)?;
 
-    // ensure enough coins provided
-    ensure_has_coins_for_game(&env, &base_bet)?;
+    // ensure base bet fits into the limits of it's denom
+    accepted_denom(&deps.storage, &base_bet.denom)?.ensure_accepts(&base_bet)?;
+
+    // ensure game can be played by the given number of players
+    ensure_correct_max_players(max_players)?;
+
+    // ensure game can be played with the given number of dices and reroll rounds
+    ensure_correct_num_of_dices(num_of_dices)?;
+    ensure_correct_reroll_rounds(reroll_rounds)?;
+
+    // ensure match series can be played by the given number of players
+    ensure_correct_series(best_of, max_players)?;
+
+    // ensure enough coins provided for all the games of the series
+    let locked = locked_per_player(&base_bet, num_of_dices, reroll_rounds, best_of);
+    ensure_has_coins_for_game(&env, &locked)?;
+
+    // refund the coins sent over the locked amount and the stray denoms
+    let token = whitelisted_token(&deps.storage, &base_bet.denom)?;
+    let messages = refund_surplus(&env, &locked, token.as_ref())?;
+
+    // hold the locked funds on behalf of the host player
+    add_to_escrow(&mut deps.storage, locked, token.as_ref())?;
 
     let game_id = load_last_game_index(&deps.storage)?;
 
-    // create new game with provided host player secret
-    let game = Game::new(env.message.sender, nft_id, base_bet);
-    let game_details = GameDetails::new(game, secret.to_be_bytes());
+    // snapshot active scoring table and rake, so they can't change mid-game
+    let scoring_table = load_scoring_table(&deps.storage)?;
+    let rake = load_rake(&deps.storage)?;
+
+    // create new game with provided host player secret hash
+    let mut game = Game::new(
+        env.message.sender,
+        nft_id,
+        base_bet,
+        shielded,
+        max_players,
+        num_of_dices,
+        reroll_rounds,
+        scoring_mode,
+        scoring_table,
+        rake,
+        best_of,
+    );
+    game.created_height = env.block.height;
+    game.host_xp = host_xp;
+    game.invitation = Invitation::new(invitees, invite_code_hash);
+    game.token = token;
+    game.record_action(&env.block);
+    let game_details = GameDetails::new(game, secret_hash);
 
     // save newly initialized game
     save_game(&mut deps.storage, game_id, &game_details)?;
@@ -254,7 +477,7 @@ pub fn create_new_game_room<S: Storage, A: Api, Q: Querier>(
     save_last_game_index(&mut deps.storage, &(game_id + 1))?;
 
     Ok(HandleResponse {
-        messages: vec![],
+        messages,
         log: vec![log("game room created, id: ", game_id)],
         data: None,
     })
@@ -265,9 +488,13 @@ pub fn join_game<S: Storage, A: Api, Q: Querier>(
     env: Env,
     nft_id: String,
     game_id: GameId,
-    secret: Secret,
+    secret_hash: Binary,
     permit: Permit,
+    invite_code: Option<Binary>,
 ) -> ContractResult<HandleResponse> {
+    // Ensure commitment to the secret is a sha256 hash
+    let secret_hash = to_secret_hash(secret_hash)?;
+
     // Ensure given account joined dao, retrieve it's nfts.
     let player_nfts = query_player_nfts(deps, &env.message.sender, permit)?;
 
@@ -277,30 +504,473 @@ pub fn join_game<S: Storage, A: Api, Q: Querier>(
     // ensure game exists
     let mut game_details = load_game(&deps.storage, game_id)?;
 
+    // ensure player is invited to the private game
+    game_details
+        .game
+        .ensure_is_invited(&env.message.sender, invite_code.as_ref())?;
+
     let base_bet = game_details.game.base_bet.clone();
 
     // Ensure player can use given nft in a game
-    ensure_can_use_nft_in_a_game(deps, nft_id.clone(), &base_bet)?;
+    ensure_can_use_nft_in_a_game(deps, nft_id.clone(), &base_bet, game_details.game.shielded)?;
 
     // ensure enough coins provided
-    ensure_has_coins_for_game(&env, &base_bet)?;
+    let locked = game_details.game.locked_per_player();
+    ensure_has_coins_for_game(&env, &locked)?;
+
+    // refund the coins sent over the locked amount and the stray denoms
+    let messages = refund_surplus(&env, &locked, game_details.game.token.as_ref())?;
 
     // ensure game status is set to pending
     game_details.ensure_is_pending()?;
 
+    // ensure player did not take a seat in the game yet
+    game_details
+        .game
+        .ensure_is_not_a_player(&env.message.sender)?;
+
+    // ensure nobody can join expired game
+    let pending_game_expiration = load_pending_game_expiration(&deps.storage)?;
+    game_details.ensure_is_not_expired(&env.block, pending_game_expiration)?;
+
+    // hold the locked funds on behalf of the joined player
+    add_to_escrow(&mut deps.storage, locked, game_details.game.token.as_ref())?;
+
     // join the game
-    game_details.join(env.message.sender, nft_id, secret.to_be_bytes());
+    game_details.join(env.message.sender, nft_id, secret_hash);
+    game_details.game.record_action(&env.block);
 
     // save updated game state
     save_game(&mut deps.storage, game_id, &game_details)?;
 
     Ok(HandleResponse {
-        messages: vec![],
+        messages,
         log: vec![log("joined the game", game_id)],
         data: None,
     })
 }
 
+/// Handle SNIP-20 tokens sent to the contract, creating or joining the game on behalf of the owner
+pub fn receive<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    mut env: Env,
+    from: HumanAddr,
+    amount: Uint128,
+    msg: Option<Binary>,
+) -> ContractResult<HandleResponse> {
+    // ensure tokens are sent by the whitelisted token contract
+    let token = whitelisted_token(&deps.storage, env.message.sender.as_str())?
+        .ok_or_else(|| StdError::generic_err(ContractError::TokenNotWhitelisted {}.to_string()))?;
+
+    let msg: ReceiveMsg =
+        from_binary(&msg.ok_or_else(|| {
+            StdError::generic_err(ContractError::InvalidReceiveMsg {}.to_string())
+        })?)?;
+
+    // tokens owner acts as the player, sent tokens are locked as the coins of the token denom
+    env.message.sender = from;
+    env.message.sent_funds = vec![coin(amount.u128(), token.denom())];
+
+    match msg {
+        ReceiveMsg::CreateNewGameRoom {
+            nft_id,
+            base_bet,
+            secret_hash,
+            permit,
+            shielded,
+            max_players,
+            num_of_dices,
+            reroll_rounds,
+            invitees,
+            invite_code_hash,
+            scoring_mode,
+            best_of,
+        } => create_new_game_room(
+            deps,
+            env,
+            nft_id,
+            coin(base_bet.u128(), token.denom()),
+            secret_hash,
+            permit,
+            shielded.unwrap_or(false),
+            max_players.unwrap_or(MIN_PLAYERS),
+            num_of_dices.unwrap_or(DEFAULT_NUM_OF_DICES),
+            reroll_rounds.unwrap_or(DEFAULT_REROLL_ROUNDS),
+            scoring_mode.unwrap_or_default(),
+            invitees.unwrap_or_default(),
+            invite_code_hash,
+            best_of.unwrap_or(SINGLE_GAME),
+        ),
+        ReceiveMsg::JoinGame {
+            nft_id,
+            game_id,
+            secret_hash,
+            permit,
+            invite_code,
+        } => join_game(deps, env, nft_id, game_id, secret_hash, permit, invite_code),
+    }
+}
+
+pub fn queue_for_match<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    nft_id: String,
+    base_bet: Coin,
+    secret_hash: Binary,
+    permit: Permit,
+    window: MatchWindow,
+) -> ContractResult<HandleResponse> {
+    // Ensure commitment to the secret is a sha256 hash
+    let secret_hash = to_secret_hash(secret_hash)?;
+
+    // Ensure given account joined dao, retrieve it's nfts.
+    let player_nfts = query_player_nfts(deps, &env.message.sender, permit)?;
+
+    // Ensure given nft belongs to player
+    ensure_can_access_nft(player_nfts, &nft_id)?;
+
+    // ensure base bet is greater then zero
+    ensure_correct_base_bet(&base_bet)?;
+
+    // ensure base bet fits into the limits of it's denom
+    accepted_denom(&deps.storage, &base_bet.denom)?.ensure_accepts(&base_bet)?;
+
+    // ensure base bet is in the native coins, deposits are sent along with the message
+    ensure_is_native_denom(&deps.storage, &base_bet.denom)?;
+
+    // Ensure player can use given nft in a game
+    let xp = ensure_can_use_nft_in_a_game(deps, nft_id.clone(), &base_bet, false)?;
+
+    // ensure enough coins provided for the game with the default settings
+    let deposit = locked_per_player(
+        &base_bet,
+        DEFAULT_NUM_OF_DICES,
+        DEFAULT_REROLL_ROUNDS,
+        SINGLE_GAME,
+    );
+    ensure_has_coins_for_game(&env, &deposit)?;
+
+    // refund the coins sent over the deposit and the stray denoms
+    let messages = refund_surplus(&env, &deposit, None)?;
+
+    // ensure player does not wait in the queue yet
+    let mut queue = load_match_queue(&deps.storage, &base_bet)?;
+    if queue.contains(&env.message.sender) {
+        return Err(StdError::generic_err(
+            ContractError::AlreadyQueued {}.to_string(),
+        ));
+    }
+
+    // hold the deposit on behalf of the player, until they leave the queue or the game is completed
+    add_to_escrow(&mut deps.storage, deposit.clone(), None)?;
+
+    let player_raw = deps.api.canonical_address(&env.message.sender)?;
+    let entry = QueueEntry {
+        player: env.message.sender.clone(),
+        nft_id,
+        secret_hash,
+        xp,
+        rating: load_player_rating(&deps.storage, &player_raw)?.rating,
+        window,
+        deposit,
+    };
+
+    let opponent = match queue.take_match(&entry) {
+        Some(opponent) => opponent,
+        None => {
+            // wait for the opponent
+            queue.entries.push(entry);
+            save_match_queue(&mut deps.storage, &base_bet, &queue)?;
+
+            return Ok(HandleResponse {
+                messages,
+                log: vec![log("queued for match", base_bet)],
+                data: None,
+            });
+        }
+    };
+    save_match_queue(&mut deps.storage, &base_bet, &queue)?;
+
+    let game_id = load_last_game_index(&deps.storage)?;
+
+    // snapshot active scoring table and rake, so they can't change mid-game
+    let scoring_table = load_scoring_table(&deps.storage)?;
+    let rake = load_rake(&deps.storage)?;
+
+    // the earlier queued player hosts the game
+    let mut game = Game::new(
+        opponent.player,
+        opponent.nft_id,
+        base_bet,
+        false,
+        MIN_PLAYERS,
+        DEFAULT_NUM_OF_DICES,
+        DEFAULT_REROLL_ROUNDS,
+        ScoringMode::default(),
+        scoring_table,
+        rake,
+        SINGLE_GAME,
+    );
+    game.created_height = env.block.height;
+    game.host_xp = opponent.xp;
+    game.record_action(&env.block);
+    let mut game_details = GameDetails::new(game, opponent.secret_hash);
+
+    // the last seat is taken, so the game starts right away
+    game_details.join(entry.player, entry.nft_id, entry.secret_hash);
+
+    // save newly started game
+    save_game(&mut deps.storage, game_id, &game_details)?;
+
+    // increment game index
+    save_last_game_index(&mut deps.storage, &(game_id + 1))?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![log("matched, game id: ", game_id)],
+        data: None,
+    })
+}
+
+pub fn leave_queue<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    base_bet: Coin,
+) -> ContractResult<HandleResponse> {
+    // ensure player waits in the queue
+    let mut queue = load_match_queue(&deps.storage, &base_bet)?;
+    let entry = queue
+        .remove(&env.message.sender)
+        .ok_or_else(|| StdError::generic_err(ContractError::NotQueued {}.to_string()))?;
+    save_match_queue(&mut deps.storage, &base_bet, &queue)?;
+
+    // refund the locked funds
+    release_from_escrow(&mut deps.storage, entry.deposit.clone())?;
+    let messages = vec![CosmosMsg::Bank(BankMsg::Send {
+        from_address: env.contract.address,
+        to_address: entry.player,
+        amount: vec![entry.deposit],
+    })];
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![log("left the queue", base_bet)],
+        data: None,
+    })
+}
+
+pub fn create_tournament<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    bracket_size: u8,
+    entry_fee: Coin,
+    min_xp: u32,
+    prize_split: Vec<u16>,
+) -> ContractResult<HandleResponse> {
+    // Ensure given account joined dao
+    ensure_is_dao_member(deps, &env.message.sender)?;
+
+    // ensure tournament can be played with the given settings
+    ensure_correct_tournament(bracket_size, &entry_fee, &prize_split)?;
+
+    // ensure entry fee is paid in the accepted native denom, entry fees are sent along with the message
+    accepted_denom(&deps.storage, &entry_fee.denom)?;
+    ensure_is_native_denom(&deps.storage, &entry_fee.denom)?;
+
+    let tournament_id = load_last_tournament_index(&deps.storage)?;
+
+    // snapshot active rake, so it can't change mid-tournament
+    let rake = load_rake(&deps.storage)?;
+
+    let tournament = Tournament::new(
+        env.message.sender,
+        bracket_size,
+        entry_fee,
+        min_xp,
+        prize_split,
+        rake,
+        &env.block,
+    );
+    let tournament_details = TournamentDetails {
+        tournament,
+        secrets: vec![],
+    };
+
+    save_tournament(&mut deps.storage, tournament_id, &tournament_details)?;
+
+    // increment tournament index
+    save_last_tournament_index(&mut deps.storage, &(tournament_id + 1))?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("tournament created, id: ", tournament_id)],
+        data: None,
+    })
+}
+
+pub fn register_for_tournament<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    tournament_id: TournamentId,
+    nft_id: String,
+    secret_hash: Binary,
+    permit: Permit,
+) -> ContractResult<HandleResponse> {
+    // Ensure commitment to the secret is a sha256 hash
+    let secret_hash = to_secret_hash(secret_hash)?;
+
+    // Ensure given account joined dao, retrieve it's nfts.
+    let player_nfts = query_player_nfts(deps, &env.message.sender, permit)?;
+
+    // Ensure given nft belongs to player
+    ensure_can_access_nft(player_nfts, &nft_id)?;
+
+    // ensure tournament exists and player can register for it
+    let mut tournament_details = load_tournament(&deps.storage, tournament_id)?;
+    tournament_details.tournament.ensure_is_in_registration()?;
+    tournament_details
+        .tournament
+        .ensure_is_not_registered(&env.message.sender)?;
+
+    // Ensure NFT has enough xp for the tournament
+    if query_nft_extension(deps, nft_id.clone())?.xp < tournament_details.tournament.min_xp {
+        return Err(StdError::generic_err(
+            ContractError::NotEnoughXpForTournament {}.to_string(),
+        ));
+    }
+
+    // ensure entry fee is provided
+    ensure_has_coins_for_game(&env, &tournament_details.tournament.entry_fee)?;
+
+    // refund the coins sent over the entry fee and the stray denoms
+    let messages = refund_surplus(&env, &tournament_details.tournament.entry_fee, None)?;
+
+    // hold the entry fee on behalf of the player, until the prizes are paid out
+    add_to_escrow(
+        &mut deps.storage,
+        tournament_details.tournament.entry_fee.clone(),
+        None,
+    )?;
+
+    tournament_details.register(env.message.sender.clone(), nft_id, secret_hash);
+
+    // start the tournament, once the bracket is full
+    // the last player to register can't predict the pairing, as the pool is unknown to the players
+    let entropy = mix_entropy(&mut deps.storage, &secret_hash)?;
+    if tournament_details.tournament.is_full() {
+        tournament_details.start(&env.block, &entropy);
+
+        for index in 0..tournament_details.tournament.rounds[0].len() {
+            spawn_tournament_match(deps, &env, tournament_id, &mut tournament_details, 0, index)?;
+        }
+    }
+
+    save_tournament(&mut deps.storage, tournament_id, &tournament_details)?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![log("registered for the tournament", tournament_id)],
+        data: None,
+    })
+}
+
+pub fn unregister_from_tournament<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    tournament_id: TournamentId,
+) -> ContractResult<HandleResponse> {
+    // ensure tournament exists and did not start yet
+    let mut tournament_details = load_tournament(&deps.storage, tournament_id)?;
+    tournament_details.tournament.ensure_is_in_registration()?;
+    tournament_details.unregister(&env.message.sender)?;
+
+    // refund the entry fee
+    let entry_fee = tournament_details.tournament.entry_fee.clone();
+    release_from_escrow(&mut deps.storage, entry_fee.clone())?;
+
+    save_tournament(&mut deps.storage, tournament_id, &tournament_details)?;
+
+    Ok(HandleResponse {
+        messages: vec![CosmosMsg::Bank(BankMsg::Send {
+            from_address: env.contract.address,
+            to_address: env.message.sender,
+            amount: vec![entry_fee],
+        })],
+        log: vec![log("unregistered from the tournament", tournament_id)],
+        data: None,
+    })
+}
+
+pub fn cancel_tournament<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    tournament_id: TournamentId,
+) -> ContractResult<HandleResponse> {
+    // ensure tournament exists and did not start yet
+    let mut tournament_details = load_tournament(&deps.storage, tournament_id)?;
+    tournament_details
+        .tournament
+        .ensure_is_organiser(&env.message.sender)?;
+    tournament_details.tournament.ensure_is_in_registration()?;
+
+    // refund the registered players
+    let messages = tournament_details
+        .tournament
+        .players
+        .iter()
+        .map(|player| {
+            CosmosMsg::Bank(BankMsg::Send {
+                from_address: env.contract.address.clone(),
+                to_address: player.address.clone(),
+                amount: vec![tournament_details.tournament.entry_fee.clone()],
+            })
+        })
+        .collect();
+    release_from_escrow(
+        &mut deps.storage,
+        tournament_details.tournament.prize_pool(),
+    )?;
+
+    tournament_details.tournament.status = TournamentStatus::Cancelled;
+    save_tournament(&mut deps.storage, tournament_id, &tournament_details)?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![log("tournament cancelled", tournament_id)],
+        data: None,
+    })
+}
+
+pub fn reveal_secret<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    game_id: GameId,
+    secret: Binary,
+) -> ContractResult<HandleResponse> {
+    // ensure game exists
+    let mut game_details = load_game(&deps.storage, game_id)?;
+
+    // secrets are revealed after all players joined, but before the first roll
+    game_details.ensure_is_started()?;
+
+    // ensure secret matches the commitment of the player
+    let secret = to_secret(secret)?;
+    game_details.reveal_secret(env.message.sender, secret)?;
+    game_details.game.record_action(&env.block);
+
+    // revealed secrets are kept private, so they feed the entropy pool
+    mix_entropy(&mut deps.storage, &secret)?;
+
+    // save updated game state
+    save_game(&mut deps.storage, game_id, &game_details)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("secret revealed", game_id)],
+        data: None,
+    })
+}
+
 pub fn roll<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
@@ -312,15 +982,20 @@ pub fn roll<S: Storage, A: Api, Q: Querier>(
     // ensure game status is set to started
     game_details.ensure_is_started()?;
 
+    // Ensure both players revealed their secrets
+    game_details.ensure_secrets_revealed()?;
+
     // Ensure given account can now make a roll in a game
-    game_details.ensure_can_roll(env.message.sender)?;
+    game_details.ensure_can_roll(env.message.sender.clone())?;
 
-    game_details.roll(game_id);
+    game_details.roll(game_id, &env.block)?;
+    game_details.game.record_action(&env.block);
 
     // save updated game state
     save_game(&mut deps.storage, game_id, &game_details)?;
 
-    let game_json = Json::serialize(&Game::from(game_details))?;
+    // shielded rolls are visible to the roller only
+    let game_json = Json::serialize(&Game::from(game_details).redacted(Some(&env.message.sender)))?;
 
     // check whether player can roll
     Ok(HandleResponse {
@@ -337,7 +1012,7 @@ pub fn reroll<S: Storage, A: Api, Q: Querier>(
     deps: &mut Extern<S, A, Q>,
     env: Env,
     game_id: GameId,
-    dices: [bool; NUM_OF_DICES],
+    dices: Vec<bool>,
 ) -> ContractResult<HandleResponse> {
     // ensure game exists
     let mut game_details = load_game(&deps.storage, game_id)?;
@@ -346,11 +1021,14 @@ pub fn reroll<S: Storage, A: Api, Q: Querier>(
     game_details.ensure_is_reroll()?;
 
     // Ensure given account can make a reroll in a game
-    game_details.ensure_can_roll(env.message.sender)?;
+    game_details.ensure_can_roll(env.message.sender.clone())?;
 
-    game_details.reroll(game_id, dices);
+    game_details.reroll(game_id, &env.block, dices)?;
+    game_details.game.record_action(&env.block);
 
-    let game_json = Json::serialize(&Game::from(game_details.clone()))?;
+    // shielded rolls are visible to the roller only
+    let game_json =
+        Json::serialize(&Game::from(game_details.clone()).redacted(Some(&env.message.sender)))?;
 
     // save updated game state
     save_game(&mut deps.storage, game_id, &game_details)?;
@@ -373,34 +1051,85 @@ pub fn end_game<S: Storage, A: Api, Q: Querier>(
     game_id: GameId,
 ) -> ContractResult<HandleResponse> {
     // ensure game exists
-    let game_details = load_game(&deps.storage, game_id)?;
+    let mut game_details = load_game(&deps.storage, game_id)?;
 
     // ensure game is finished and can be ended
     game_details.ensure_is_finished()?;
 
-    // determine a winner and complete payments
-    let winner = game_details.determine_a_winner();
+    // determine winners and complete payments
+    let winners = game_details.determine_winners();
 
     // Ensure actor can complete a game
-    game_details.ensure_can_complete_a_game(env.message.sender, winner)?;
+    game_details.ensure_can_complete_a_game(env.message.sender.clone(), &winners)?;
+
+    // match series is settled only once it's decided, otherwise the next game starts
+    let winners = match game_details.complete_series_game(&winners) {
+        Some(winners) => winners,
+        None => {
+            game_details.game.record_action(&env.block);
+            save_game(&mut deps.storage, game_id, &game_details)?;
+
+            return Ok(HandleResponse {
+                messages: vec![],
+                log: vec![log("series game completed", game_id)],
+                data: None,
+            });
+        }
+    };
+
+    // drawn tournament match is replayed, so a single player advances in the bracket
+    if game_details.game.tournament.is_some() && game_details.is_draw(&winners) {
+        game_details.restart(HOST);
+        game_details.game.record_action(&env.block);
+        save_game(&mut deps.storage, game_id, &game_details)?;
+
+        return Ok(HandleResponse {
+            messages: vec![],
+            log: vec![log("tournament match replayed", game_id)],
+            data: None,
+        });
+    }
 
     let game_json = Json::serialize(&Game::from(game_details.clone()))?;
 
-    // we need to increase nft xp if there is a winner
-    let set_nft_metadata_msg = if let Some(winner) = winner {
-        Some(get_set_nft_metadata_msg(deps, &game_details, winner)?)
-    } else {
-        None
-    };
+    let mut messages = game_checkout(deps, &env, &game_details, &winners)?;
+
+    let nft_ratings = update_ratings(deps, &game_details.game, &winners)?;
+
+    // new xp of the winners nfts
+    let mut nft_xps = vec![];
+
+    // we need to increase nft xp if there is no draw
+    if !game_details.is_draw(&winners) {
+        let xp_scaling = load_xp_scaling(&deps.storage)?;
 
-    let mut messages = game_details.complete_checkout(env.contract.address, winner);
+        for winner in winners.iter().copied() {
+            // average rating of the winner opponents
+            let opponents_rating = nft_ratings
+                .iter()
+                .enumerate()
+                .filter(|(player, _)| *player != winner)
+                .map(|(_, rating)| *rating as u64)
+                .sum::<u64>()
+                / (nft_ratings.len() as u64 - 1);
 
-    if let Some(msg) = set_nft_metadata_msg {
-        messages.push(msg);
+            let xp = xp_gain(nft_ratings[winner], opponents_rating as u32, xp_scaling);
+            let (set_metadata_msg, new_xp) =
+                get_set_nft_metadata_msg(deps, &game_details, winner, xp)?;
+            messages.push(set_metadata_msg);
+            nft_xps.push((winner, new_xp));
+        }
     }
 
-    // remove game after completion
-    remove_game(&mut deps.storage, game_id);
+    update_leaderboards(deps, &game_details, &winners, &nft_xps)?;
+
+    // archive game result and remove game after completion
+    archive_game_result(
+        deps,
+        game_id,
+        game_details.result(&winners, &env.block, false),
+    )?;
+    remove_game(&mut deps.storage, game_id)?;
 
     let log = vec![log(
         "game completed",
@@ -414,27 +1143,523 @@ pub fn end_game<S: Storage, A: Api, Q: Querier>(
     })
 }
 
-pub fn query<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
-    msg: QueryMsg,
-) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Game { game_id } => to_binary(&query_game(deps, game_id)?),
-        QueryMsg::GamesByStatus { status } => to_binary(&query_games_by_status(deps, status)?),
-        QueryMsg::NftAddress {} => to_binary(&query_nft_address(deps)?),
-        QueryMsg::PlayerNfts { player, permit } => {
-            to_binary(&query_player_nfts(deps, &player, permit)?)
-        }
-        QueryMsg::NftInfo { token_id } => to_binary(&query_nft_info_by_id(deps, token_id)?),
-    }
-}
-
-// query game by it's id
-fn query_game<S: Storage, A: Api, Q: Querier>(
-    deps: &Extern<S, A, Q>,
+pub fn claim_timeout<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    game_id: GameId,
+) -> ContractResult<HandleResponse> {
+    // ensure game exists
+    let game_details = load_game(&deps.storage, game_id)?;
+
+    let turn_timeout = load_turn_timeout(&deps.storage)?;
+
+    // Ensure the opponents of the actor did not make a move in time
+    let winners =
+        game_details.ensure_can_claim_timeout(&env.message.sender, &env.block, turn_timeout)?;
+
+    let game_json = Json::serialize(&Game::from(game_details.clone()))?;
+
+    // stalled players forfeit the game
+    let messages = game_checkout(deps, &env, &game_details, &winners)?;
+
+    // abandoned game is not rated, as nobody played it
+    if !game_details.is_abandoned() {
+        update_ratings(deps, &game_details.game, &winners)?;
+        update_leaderboards(deps, &game_details, &winners, &[])?;
+    }
+
+    // archive game result
+    archive_game_result(
+        deps,
+        game_id,
+        game_details.result(&winners, &env.block, true),
+    )?;
+
+    // remove game after completion
+    remove_game(&mut deps.storage, game_id)?;
+
+    let log = vec![log(
+        "game forfeited",
+        format!("game_id {} \n {:?} ", game_id, game_json),
+    )];
+
+    Ok(HandleResponse {
+        messages,
+        log,
+        data: None,
+    })
+}
+
+pub fn cancel_game<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    game_id: GameId,
+) -> ContractResult<HandleResponse> {
+    // ensure game exists
+    let game_details = load_game(&deps.storage, game_id)?;
+
+    // Ensure actor can cancel pending game
+    let pending_game_expiration = load_pending_game_expiration(&deps.storage)?;
+    game_details.ensure_can_cancel(&env.message.sender, &env.block, pending_game_expiration)?;
+
+    // refund host player
+    let messages = game_details.cancel_checkout(env.contract.address)?;
+    release_from_escrow(
+        &mut deps.storage,
+        coin(
+            game_details.game.game_pool.total_deposits(),
+            &game_details.game.base_bet.denom,
+        ),
+    )?;
+
+    // remove cancelled game
+    remove_game(&mut deps.storage, game_id)?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![log("game cancelled", game_id)],
+        data: None,
+    })
+}
+
+pub fn set_turn_timeout<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    blocks: u64,
+) -> ContractResult<HandleResponse> {
+    ensure_is_admin(deps, &env.message.sender)?;
+    ensure_correct_turn_timeout(blocks)?;
+
+    save_turn_timeout(&mut deps.storage, blocks)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("turn timeout set", blocks)],
+        data: None,
+    })
+}
+
+pub fn set_pending_game_expiration<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    blocks: Option<u64>,
+) -> ContractResult<HandleResponse> {
+    ensure_is_admin(deps, &env.message.sender)?;
+
+    save_pending_game_expiration(&mut deps.storage, blocks)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("pending game expiration set", format!("{:?}", blocks))],
+        data: None,
+    })
+}
+
+pub fn set_scoring_table<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    scoring_table: ScoringTable,
+) -> ContractResult<HandleResponse> {
+    ensure_is_admin(deps, &env.message.sender)?;
+
+    save_scoring_table(&mut deps.storage, &scoring_table)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("scoring table set", format!("{:?}", scoring_table))],
+        data: None,
+    })
+}
+
+pub fn set_xp_tiers<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    xp_tiers: Vec<XpTier>,
+) -> ContractResult<HandleResponse> {
+    ensure_is_admin(deps, &env.message.sender)?;
+
+    ensure_correct_xp_tiers(&xp_tiers)?;
+
+    save_xp_tiers(&mut deps.storage, &xp_tiers)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("xp tiers set", format!("{:?}", xp_tiers))],
+        data: None,
+    })
+}
+
+pub fn set_accepted_denoms<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    accepted_denoms: Vec<AcceptedDenom>,
+) -> ContractResult<HandleResponse> {
+    ensure_is_admin(deps, &env.message.sender)?;
+
+    ensure_correct_accepted_denoms(&accepted_denoms)?;
+
+    save_accepted_denoms(&mut deps.storage, &accepted_denoms)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("accepted denoms set", format!("{:?}", accepted_denoms))],
+        data: None,
+    })
+}
+
+pub fn set_rake<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    rake: u16,
+) -> ContractResult<HandleResponse> {
+    ensure_is_admin(deps, &env.message.sender)?;
+
+    ensure_correct_rake(rake)?;
+
+    save_rake(&mut deps.storage, rake)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("rake set", rake)],
+        data: None,
+    })
+}
+
+pub fn treasury_withdraw<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    recipient: HumanAddr,
+    amount: Coin,
+) -> ContractResult<HandleResponse> {
+    ensure_is_admin(deps, &env.message.sender)?;
+
+    let log = vec![log(
+        "treasury withdrawn",
+        format!("{} to {}", amount, recipient),
+    )];
+
+    let message =
+        withdraw_from_treasury(&mut deps.storage, env.contract.address, recipient, amount)?;
+
+    Ok(HandleResponse {
+        messages: vec![message],
+        log,
+        data: None,
+    })
+}
+
+pub fn set_xp_scaling<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    enabled: bool,
+) -> ContractResult<HandleResponse> {
+    ensure_is_admin(deps, &env.message.sender)?;
+
+    save_xp_scaling(&mut deps.storage, enabled)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("xp scaling set", enabled)],
+        data: None,
+    })
+}
+
+pub fn set_token_whitelist<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    tokens: Vec<Snip20Token>,
+) -> ContractResult<HandleResponse> {
+    ensure_is_admin(deps, &env.message.sender)?;
+
+    save_token_whitelist(&mut deps.storage, &tokens)?;
+
+    Ok(HandleResponse {
+        messages: register_receive_msgs(&tokens, env.contract_code_hash)?,
+        log: vec![log("token whitelist set", tokens.len())],
+        data: None,
+    })
+}
+
+pub fn set_leaderboard_opt_out<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    opt_out: bool,
+) -> ContractResult<HandleResponse> {
+    let player_raw = deps.api.canonical_address(&env.message.sender)?;
+
+    save_leaderboard_opt_out(&mut deps.storage, &player_raw, opt_out)?;
+
+    // remove player from all the leaderboards right away
+    if opt_out {
+        let mut kinds = vec![
+            LeaderboardKind::Wins,
+            LeaderboardKind::Xp,
+            LeaderboardKind::Rating,
+        ];
+        kinds.extend(
+            load_leaderboard_denoms(&deps.storage)?
+                .into_iter()
+                .map(|denom| LeaderboardKind::NetWinnings { denom }),
+        );
+
+        for kind in kinds {
+            let mut leaderboard = load_leaderboard(&deps.storage, &kind)?;
+            leaderboard.remove(&env.message.sender);
+            save_leaderboard(&mut deps.storage, &kind, &leaderboard)?;
+        }
+    }
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("leaderboard opt out set", opt_out)],
+        data: None,
+    })
+}
+
+pub fn submit_proposal<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    description: String,
+    kind: ProposalKind,
+) -> ContractResult<HandleResponse> {
+    // Ensure given account joined dao
+    ensure_is_dao_member(deps, &env.message.sender)?;
+
+    // Ensure proposal action can be executed
+    ensure_correct_proposal_kind(&kind)?;
+
+    let proposal_id = load_last_proposal_index(&deps.storage)?;
+
+    let governance_config = load_governance_config(&deps.storage)?;
+    let proposal = Proposal::new(
+        env.message.sender,
+        description,
+        kind,
+        &env.block,
+        &governance_config,
+    );
+
+    save_proposal(&mut deps.storage, proposal_id, &proposal)?;
+
+    // increment proposal index
+    save_last_proposal_index(&mut deps.storage, &(proposal_id + 1))?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("proposal submitted", proposal_id)],
+        data: None,
+    })
+}
+
+pub fn cast_vote<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    proposal_id: ProposalId,
+    nft_id: String,
+    permit: Permit,
+    vote: VoteOption,
+) -> ContractResult<HandleResponse> {
+    let mut proposal = load_proposal(&deps.storage, proposal_id)?;
+
+    // Ensure voting period has not ended yet
+    proposal.ensure_is_open(&env.block)?;
+
+    // Ensure given account joined dao, retrieve it's nfts.
+    let player_nfts = query_player_nfts(deps, &env.message.sender, permit)?;
+
+    // Ensure given nft belongs to player
+    ensure_can_access_nft(player_nfts, &nft_id)?;
+
+    // Ensure each nft votes only once, even if it's transferred to another member
+    if load_vote(&deps.storage, proposal_id, &nft_id).is_ok() {
+        return Err(StdError::generic_err(
+            ContractError::NftAlreadyVoted {}.to_string(),
+        ));
+    }
+
+    // Ensure nft played, NFTs without xp have no voice
+    let weight = vote_weight(query_nft_extension(deps, nft_id.clone())?.xp);
+    if weight == 0 {
+        return Err(StdError::generic_err(
+            ContractError::NoVotingPower {}.to_string(),
+        ));
+    }
+    proposal.vote(vote, weight);
+
+    save_proposal(&mut deps.storage, proposal_id, &proposal)?;
+    save_vote(&mut deps.storage, proposal_id, &nft_id)?;
+
+    Ok(HandleResponse {
+        messages: vec![],
+        log: vec![log("vote cast", proposal_id)],
+        data: None,
+    })
+}
+
+pub fn execute_proposal<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: Env,
+    proposal_id: ProposalId,
+) -> ContractResult<HandleResponse> {
+    let mut proposal = load_proposal(&deps.storage, proposal_id)?;
+
+    // Ensure voting period has ended
+    proposal.ensure_can_execute(&env.block)?;
+
+    let mut messages = vec![];
+
+    proposal.status = if proposal.is_passed() {
+        messages = execute_proposal_kind(
+            &mut deps.storage,
+            env.contract.address,
+            env.contract_code_hash,
+            proposal.kind.clone(),
+        )?;
+        ProposalStatus::Executed
+    } else {
+        ProposalStatus::Rejected
+    };
+
+    save_proposal(&mut deps.storage, proposal_id, &proposal)?;
+
+    Ok(HandleResponse {
+        messages,
+        log: vec![log(
+            "proposal closed",
+            format!("{} {:?}", proposal_id, proposal.status),
+        )],
+        data: None,
+    })
+}
+
+pub fn query<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    msg: QueryMsg,
+) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Game { game_id } => to_binary(&query_game(deps, game_id)?),
+        QueryMsg::PlayerGame {
+            game_id,
+            player,
+            permit,
+        } => to_binary(&query_player_game(deps, game_id, player, permit)?),
+        QueryMsg::GamesByStatus {
+            status,
+            start_after,
+            limit,
+        } => to_binary(&query_games_by_status(deps, status, start_after, limit)?),
+        QueryMsg::OpenRooms {
+            filter,
+            viewer,
+            start_after,
+            limit,
+        } => to_binary(&query_open_rooms(deps, filter, viewer, start_after, limit)?),
+        QueryMsg::GamesByPlayer {
+            player,
+            start_after,
+            limit,
+        } => to_binary(&query_games_by_player(deps, player, start_after, limit)?),
+        QueryMsg::GameResult { game_id } => to_binary(&load_game_result(&deps.storage, game_id)?),
+        QueryMsg::PlayerGameHistory {
+            player,
+            permit,
+            start_after,
+            limit,
+        } => to_binary(&query_player_game_history(
+            deps,
+            player,
+            permit,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::TurnTimeout {} => to_binary(&load_turn_timeout(&deps.storage)?),
+        QueryMsg::PendingGameExpiration {} => {
+            to_binary(&load_pending_game_expiration(&deps.storage)?)
+        }
+        QueryMsg::ScoringTable {} => to_binary(&load_scoring_table(&deps.storage)?),
+        QueryMsg::XpTiers {} => to_binary(&load_xp_tiers(&deps.storage)?),
+        QueryMsg::Rake {} => to_binary(&load_rake(&deps.storage)?),
+        QueryMsg::Treasury {} => to_binary(&load_treasury(&deps.storage)?),
+        QueryMsg::Solvency {} => to_binary(&query_solvency(deps)?),
+        QueryMsg::XpScaling {} => to_binary(&load_xp_scaling(&deps.storage)?),
+        QueryMsg::AcceptedDenoms {} => to_binary(&load_accepted_denoms(&deps.storage)?),
+        QueryMsg::TokenWhitelist {} => to_binary(&load_token_whitelist(&deps.storage)?),
+        QueryMsg::PlayerRating { player } => to_binary(&query_player_rating(deps, player)?),
+        QueryMsg::NftRating { nft_id } => to_binary(&load_nft_rating(&deps.storage, &nft_id)?),
+        QueryMsg::Leaderboard {
+            kind,
+            start_after,
+            limit,
+        } => to_binary(&query_leaderboard(deps, kind, start_after, limit)?),
+        QueryMsg::GovernanceConfig {} => to_binary(&load_governance_config(&deps.storage)?),
+        QueryMsg::Proposal { proposal_id } => {
+            to_binary(&load_proposal(&deps.storage, proposal_id)?)
+        }
+        QueryMsg::Proposals { start_after, limit } => {
+            to_binary(&query_proposals(deps, start_after, limit)?)
+        }
+        QueryMsg::Tournament { tournament_id } => to_binary(&Tournament::from(load_tournament(
+            &deps.storage,
+            tournament_id,
+        )?)),
+        QueryMsg::Tournaments { start_after, limit } => {
+            to_binary(&query_tournaments(deps, start_after, limit)?)
+        }
+        QueryMsg::NftAddress {} => to_binary(&query_nft_address(deps)?),
+        QueryMsg::PlayerNfts { player, permit } => {
+            to_binary(&query_player_nfts(deps, &player, permit)?)
+        }
+        QueryMsg::NftInfo { token_id } => to_binary(&query_nft_info_by_id(deps, token_id)?),
+    }
+}
+
+// query game by it's id
+fn query_game<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
     game_id: GameId,
 ) -> StdResult<Game> {
-    load_game(&deps.storage, game_id).map(Game::from)
+    load_game(&deps.storage, game_id).map(|game_details| Game::from(game_details).redacted(None))
+}
+
+// query game by it's id on behalf of the player, authenticated by the permit
+fn query_player_game<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    game_id: GameId,
+    player: HumanAddr,
+    permit: Permit,
+) -> StdResult<Game> {
+    let game = Game::from(load_game(&deps.storage, game_id)?);
+
+    // Ensure permit is signed by the player and grants access to the player nfts
+    ensure_permit_signed_by(deps, &player, &permit)?;
+    let player_nfts = query_player_nfts(deps, &player, permit)?;
+
+    // Ensure player takes part in the game with one of the player nfts
+    let seat = game.ensure_is_player(&player)?;
+    ensure_can_access_nft(player_nfts, &game.seats[seat].nft_id)?;
+
+    Ok(game.redacted(Some(&player)))
+}
+
+// returns results of the games, player completed, and their ids, the latest one goes first
+fn query_player_game_history<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    player: HumanAddr,
+    permit: Permit,
+    start_after: Option<GameId>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(GameId, GameResult)>> {
+    // Ensure permit is signed by the player and grants access to the player nfts
+    ensure_permit_signed_by(deps, &player, &permit)?;
+    query_player_nfts(deps, &player, permit)?;
+
+    let player_raw = deps.api.canonical_address(&player)?;
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT);
+
+    load_player_games(&deps.storage, &player_raw, start_after, limit)?
+        .into_iter()
+        .map(|game_id| load_game_result(&deps.storage, game_id).map(|result| (game_id, result)))
+        .collect()
 }
 
 fn query_nft_address<S: Storage, A: Api, Q: Querier>(
@@ -443,25 +1668,228 @@ fn query_nft_address<S: Storage, A: Api, Q: Querier>(
     nft_address(&deps.storage)
 }
 
-// returns a vector of [u8] game keys and their details
+// returns a page of the games with the given status and their ids
 fn query_games_by_status<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
     status: GameStatus,
+    start_after: Option<GameId>,
+    limit: Option<u32>,
 ) -> StdResult<Vec<(GameId, Game)>> {
-    (INIT_INDEX..=load_last_game_index(&deps.storage)?)
+    let game_ids = load_status_games(&deps.storage, &status, start_after);
+    query_games_page(deps, game_ids, limit, is_publicly_listed)
+}
+
+// returns a page of the active games, player is seated at, and their ids
+fn query_games_by_player<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    player: HumanAddr,
+    start_after: Option<GameId>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(GameId, Game)>> {
+    // index is keyed by the addresses in their canonical human readable form
+    let player = deps
+        .api
+        .human_address(&deps.api.canonical_address(&player)?)?;
+    let game_ids = load_player_active_games(&deps.storage, &player, start_after);
+    query_games_page(deps, game_ids, limit, is_publicly_listed)
+}
+
+// private rooms are left out of the public listings, while they are pending
+fn is_publicly_listed(game: &Game) -> bool {
+    !(game.status == GameStatus::Pending && game.is_private())
+}
+
+// returns a page of the pending game rooms summaries, passing the filter, and their ids
+fn query_open_rooms<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    filter: Option<RoomFilter>,
+    viewer: Option<Viewer>,
+    start_after: Option<GameId>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(GameId, RoomSummary)>> {
+    // Ensure permit is signed by the viewer, so the viewer is proven to be invited
+    let viewer = match viewer {
+        Some(viewer) => {
+            ensure_permit_signed_by(deps, &viewer.address, &viewer.permit)?;
+            ensure_is_dao_member(deps, &viewer.address)?;
+            Some(viewer.address)
+        }
+        None => None,
+    };
+
+    let filter = filter.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT);
+    let xp_tiers = load_xp_tiers(&deps.storage)?;
+
+    // games join the pending index once they are created, so the index is sorted by the creation height
+    let mut rooms = vec![];
+    for game_id in load_status_games(&deps.storage, &GameStatus::Pending, start_after) {
+        let game_id = game_id?;
+        let game = load_game(&deps.storage, game_id)?.game;
+        let visible = match &viewer {
+            Some(viewer) => game.is_visible_to(viewer),
+            None => !game.is_private(),
+        };
+        if !visible {
+            continue;
+        }
+
+        let host_xp_tier = xp_tiers
+            .iter()
+            .rposition(|tier| tier.min_xp <= game.host_xp)
+            .unwrap_or_default() as u8;
+        let room = game.room_summary(host_xp_tier);
+
+        if filter.matches(&room) {
+            rooms.push((game_id, room));
+            if rooms.len() == limit as usize {
+                break;
+            }
+        }
+    }
+
+    Ok(rooms)
+}
+
+// loads the visible games from the ids index, following the given id
+fn query_games_page<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    game_ids: impl Iterator<Item = StdResult<GameId>>,
+    limit: Option<u32>,
+    is_visible: impl Fn(&Game) -> bool,
+) -> StdResult<Vec<(GameId, Game)>> {
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT);
+
+    let mut games = vec![];
+    for game_id in game_ids {
+        let game_id = game_id?;
+        let game = Game::from(load_game(&deps.storage, game_id)?);
+        if is_visible(&game) {
+            games.push((game_id, game.redacted(None)));
+            if games.len() == limit as usize {
+                break;
+            }
+        }
+    }
+
+    Ok(games)
+}
+
+// query rating of the player address
+fn query_player_rating<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    player: HumanAddr,
+) -> StdResult<Rating> {
+    let player_raw = deps.api.canonical_address(&player)?;
+    load_player_rating(&deps.storage, &player_raw)
+}
+
+// returns leaderboard entries, following the given rank, and their ranks
+fn query_leaderboard<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    kind: LeaderboardKind,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u32, LeaderboardEntry)>> {
+    let start = start_after.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT);
+
+    Ok(load_leaderboard(&deps.storage, &kind)?
+        .entries
         .into_iter()
-        .map(|i| match load_game(&deps.storage, i) {
-            Ok(game) => Ok((i, Game::from(game))),
-            Err(e) => Err(e),
+        .enumerate()
+        .map(|(i, entry)| (i as u32 + 1, entry))
+        .skip(start as usize)
+        .take(limit as usize)
+        .collect())
+}
+
+// returns proposals, following the given one, and their ids
+fn query_proposals<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_after: Option<ProposalId>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(ProposalId, Proposal)>> {
+    let start = start_after.map(|id| id + 1).unwrap_or(INIT_INDEX);
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT);
+
+    (start..load_last_proposal_index(&deps.storage)?)
+        .take(limit as usize)
+        .map(|i| load_proposal(&deps.storage, i).map(|proposal| (i, proposal)))
+        .collect()
+}
+
+// returns tournaments, following the given id, and their ids
+fn query_tournaments<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    start_after: Option<TournamentId>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(TournamentId, Tournament)>> {
+    let start = start_after.map(|id| id + 1).unwrap_or(INIT_INDEX);
+    let limit = limit.unwrap_or(DEFAULT_QUERY_LIMIT).min(MAX_QUERY_LIMIT);
+
+    (start..load_last_tournament_index(&deps.storage)?)
+        .take(limit as usize)
+        .map(|i| {
+            load_tournament(&deps.storage, i)
+                .map(|tournament_details| (i, Tournament::from(tournament_details)))
         })
-        .filter(|game_entry| {
-            if let Ok((_, game)) = game_entry {
-                game.status == status
+        .collect()
+}
+
+/// Compare the escrow and the treasury with the contract balance per each denom
+/// SNIP-20 token denoms are the contract addresses, their balances are left unchecked
+fn query_solvency<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+) -> StdResult<SolvencyResponse> {
+    let contract_address = load_contract_address(&deps.storage)?;
+    let escrow = load_escrow(&deps.storage)?;
+    let treasury = load_treasury(&deps.storage)?;
+
+    let mut denoms: Vec<String> = vec![];
+    for held in escrow.iter().chain(treasury.iter()) {
+        if !denoms.contains(&held.denom) {
+            denoms.push(held.denom.clone());
+        }
+    }
+
+    let amount_of = |held: &[Coin], denom: &str| {
+        held.iter()
+            .find(|balance| balance.denom == denom)
+            .map(|balance| balance.amount)
+            .unwrap_or_default()
+    };
+
+    let denoms = denoms
+        .into_iter()
+        .map(|denom| {
+            let balance = if held_token(&deps.storage, &denom)?.is_some() {
+                None
             } else {
-                false
-            }
+                Some(
+                    deps.querier
+                        .query_balance(&contract_address, &denom)?
+                        .amount,
+                )
+            };
+
+            Ok(DenomSolvency {
+                escrow: amount_of(&escrow, &denom),
+                treasury: amount_of(&treasury, &denom),
+                balance,
+                denom,
+            })
         })
-        .collect()
+        .collect::<StdResult<Vec<DenomSolvency>>>()?;
+
+    let solvent = denoms.iter().all(|denom| {
+        denom
+            .balance
+            .map(|balance| balance.u128() >= denom.escrow.u128() + denom.treasury.u128())
+            .unwrap_or(true)
+    });
+
+    Ok(SolvencyResponse { solvent, denoms })
 }
 
 pub fn to_permit_msg(permit: Permit, query: QueryWithPermit) -> NftQueryMsg {
@@ -520,6 +1948,21 @@ fn query_nft_info_by_id<S: Storage, A: Api, Q: Querier>(
     }))
 }
 
+/// Query dice nft extension by it's id
+fn query_nft_extension<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    token_id: String,
+) -> ContractResult<Extension> {
+    let nft_info = query_nft_info_by_id(deps, token_id)?;
+    if let NftQueryAnswer::NftInfo { extension, .. } = nft_info {
+        extension.ok_or_else(|| StdError::generic_err("NFT extension is not set"))
+    } else {
+        Err(StdError::generic_err(
+            "unable to get NftInfo from nft contract",
+        ))
+    }
+}
+
 /// Ensure given account is contract admin
 fn ensure_is_admin<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
@@ -536,9 +1979,9 @@ fn ensure_is_admin<S: Storage, A: Api, Q: Querier>(
 }
 
 /// Check whether given player provided max amount of coins, that can potentially be lost in the game
-pub fn ensure_has_coins_for_game(env: &Env, base_bet: &Coin) -> ContractResult<()> {
-    // should be at least 10 x base_bet
-    if !has_coins(&env.message.sent_funds, &locked_per_player(base_bet)) {
+pub fn ensure_has_coins_for_game(env: &Env, locked_per_player: &Coin) -> ContractResult<()> {
+    // should be at least base_bet x num of dices x num of rounds
+    if !has_coins(&env.message.sent_funds, locked_per_player) {
         Err(StdError::generic_err(
             ContractError::NotEnoughTokensForTheGame {}.to_string(),
         ))
@@ -547,6 +1990,63 @@ pub fn ensure_has_coins_for_game(env: &Env, base_bet: &Coin) -> ContractResult<(
     }
 }
 
+/// Messages, refunding the coins sent over the locked amount and the coins of the other denoms
+/// Surplus of the SNIP-20 token is transferred back, the native coins are sent back
+pub fn refund_surplus(
+    env: &Env,
+    locked: &Coin,
+    token: Option<&Snip20Token>,
+) -> StdResult<Vec<CosmosMsg>> {
+    env.message
+        .sent_funds
+        .iter()
+        .filter_map(|sent| {
+            let surplus = if sent.denom == locked.denom {
+                sent.amount.u128().saturating_sub(locked.amount.u128())
+            } else {
+                sent.amount.u128()
+            };
+            if surplus > 0 {
+                Some(coin(surplus, &sent.denom))
+            } else {
+                None
+            }
+        })
+        .map(|surplus| {
+            payout_msg(
+                token.filter(|token| token.denom() == surplus.denom),
+                env.contract.address.clone(),
+                env.message.sender.clone(),
+                surplus,
+            )
+        })
+        .collect()
+}
+
+/// Convert revealed secret into the fixed size secret bytes
+pub fn to_secret(secret: Binary) -> ContractResult<Secret> {
+    let mut bytes = Secret::default();
+    if secret.len() != bytes.len() {
+        return Err(StdError::generic_err(
+            ContractError::InvalidSecret {}.to_string(),
+        ));
+    }
+    bytes.copy_from_slice(secret.as_slice());
+    Ok(bytes)
+}
+
+/// Convert commitment to the secret into the sha256 hash
+pub fn to_secret_hash(secret_hash: Binary) -> ContractResult<SecretHash> {
+    let mut hash = SecretHash::default();
+    if secret_hash.len() != hash.len() {
+        return Err(StdError::generic_err(
+            ContractError::InvalidSecretHash {}.to_string(),
+        ));
+    }
+    hash.copy_from_slice(secret_hash.as_slice());
+    Ok(hash)
+}
+
 /// Ensure base bet is greater then zero
 pub fn ensure_correct_base_bet(base_bet: &Coin) -> ContractResult<()> {
     // should be ge 0
@@ -559,6 +2059,55 @@ pub fn ensure_correct_base_bet(base_bet: &Coin) -> ContractResult<()> {
     }
 }
 
+/// Ensure game can be played by the given number of players
+pub fn ensure_correct_max_players(max_players: u8) -> ContractResult<()> {
+    if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&max_players) {
+        Err(StdError::generic_err(
+            ContractError::InvalidNumberOfPlayers {}.to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Ensure game can be played with the given number of dices
+pub fn ensure_correct_num_of_dices(num_of_dices: u8) -> ContractResult<()> {
+    if !(MIN_NUM_OF_DICES..=MAX_NUM_OF_DICES).contains(&num_of_dices) {
+        Err(StdError::generic_err(
+            ContractError::InvalidNumberOfDices {}.to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Ensure game can be played with the given number of reroll rounds
+pub fn ensure_correct_reroll_rounds(reroll_rounds: u8) -> ContractResult<()> {
+    if reroll_rounds > MAX_REROLL_ROUNDS {
+        Err(StdError::generic_err(
+            ContractError::InvalidNumberOfRerollRounds {}.to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Ensure match series of the given num of games can be played by the given number of players
+pub fn ensure_correct_series(best_of: u8, max_players: u8) -> ContractResult<()> {
+    let is_single_game = best_of == SINGLE_GAME;
+    let is_correct_series = (MIN_SERIES_GAMES..=MAX_SERIES_GAMES).contains(&best_of)
+        && best_of % 2 == 1
+        && max_players == MIN_PLAYERS;
+
+    if is_single_game || is_correct_series {
+        Ok(())
+    } else {
+        Err(StdError::generic_err(
+            ContractError::InvalidSeries {}.to_string(),
+        ))
+    }
+}
+
 /// Ensure given player joined DAO
 pub fn ensure_is_dao_member<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
@@ -585,6 +2134,37 @@ pub fn ensure_is_not_a_dao_member<S: Storage>(
     }
 }
 
+/// Ensure permit is signed by the given player, so it can't prove the access on behalf of the others
+pub fn ensure_permit_signed_by<S: Storage, A: Api, Q: Querier>(
+    deps: &Extern<S, A, Q>,
+    player: &HumanAddr,
+    permit: &Permit,
+) -> ContractResult<()> {
+    // permits are issued for the nft contract
+    let signer = validate(
+        deps,
+        PREFIX_REVOKED_PERMITS,
+        permit,
+        nft_address(&deps.storage)?,
+    )?;
+    ensure_is_permit_signer(&deps.api, player, &signer)
+}
+
+/// Ensure permit signer is the given player
+pub fn ensure_is_permit_signer<A: Api>(
+    api: &A,
+    player: &HumanAddr,
+    signer: &HumanAddr,
+) -> ContractResult<()> {
+    if api.canonical_address(player)? == api.canonical_address(signer)? {
+        Ok(())
+    } else {
+        Err(StdError::generic_err(
+            ContractError::PermitNotSignedByPlayer {}.to_string(),
+        ))
+    }
+}
+
 /// Ensure provided NFT is in a set of NFTs
 pub fn ensure_can_access_nft(player_tokens: Vec<String>, token_id: &str) -> ContractResult<()> {
     if player_tokens
@@ -604,19 +2184,526 @@ pub fn ensure_can_use_nft_in_a_game<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
     token_id: String,
     base_bet: &Coin,
-) -> ContractResult<()> {
-    let nft_info = query_nft_info_by_id(deps, token_id)?;
-    if let NftQueryAnswer::NftInfo { extension, .. } = nft_info {
-        if let Some(extension) = extension {
-            extension.ensure_enough_xp_for_the_base_bet(base_bet)
-        } else {
-            Err(StdError::generic_err("NFT extension is not set"))
+    shielded: bool,
+) -> ContractResult<u32> {
+    let accepted_denom = accepted_denom(&deps.storage, &base_bet.denom)?;
+    let extension = query_nft_extension(deps, token_id)?;
+    let xp_tiers = load_xp_tiers(&deps.storage)?;
+    extension.ensure_enough_xp_for_the_base_bet(
+        &xp_tiers,
+        &accepted_denom.xp_tier_base_bet(base_bet)?,
+    )?;
+    if shielded {
+        extension.ensure_enough_xp_for_shielded_game(&xp_tiers)?;
+    }
+    Ok(extension.xp)
+}
+
+/// Ensure proposal action can be executed
+pub fn ensure_correct_proposal_kind(kind: &ProposalKind) -> ContractResult<()> {
+    match kind {
+        ProposalKind::SetXpTiers { xp_tiers } => ensure_correct_xp_tiers(xp_tiers),
+        ProposalKind::SetAcceptedDenoms { accepted_denoms } => {
+            ensure_correct_accepted_denoms(accepted_denoms)
         }
+        ProposalKind::SetGovernanceConfig { config } => config.ensure_is_correct(),
+        ProposalKind::SetRake { rake } => ensure_correct_rake(*rake),
+        ProposalKind::SetTurnTimeout { blocks } => ensure_correct_turn_timeout(*blocks),
+        _ => Ok(()),
+    }
+}
+
+/// Ensure rake does not exceed the whole stake
+pub fn ensure_correct_rake(rake: u16) -> ContractResult<()> {
+    if rake > BASIS_POINTS {
+        Err(StdError::generic_err(
+            ContractError::InvalidRake {}.to_string(),
+        ))
     } else {
+        Ok(())
+    }
+}
+
+/// Ensure players have at least the min number of blocks to make a move
+pub fn ensure_correct_turn_timeout(blocks: u64) -> ContractResult<()> {
+    if blocks < MIN_TURN_TIMEOUT {
         Err(StdError::generic_err(
-            "unable to get NftInfo from nft contract",
+            ContractError::InvalidTurnTimeout {}.to_string(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Mix given bytes into the entropy pool, returns the updated pool
+fn mix_entropy<S: Storage>(storage: &mut S, entropy: &[u8]) -> StdResult<[u8; 32]> {
+    let pool: [u8; 32] = Sha256::digest(&[&load_entropy(storage)?[..], entropy].concat()).into();
+    save_entropy(storage, &pool)?;
+    Ok(pool)
+}
+
+/// Add the rake taken from the game to the DAO treasury
+/// Token of the rake is recorded, so it can be withdrawn even once it's removed from the whitelist
+fn add_to_treasury<S: Storage>(
+    storage: &mut S,
+    rake: Coin,
+    token: Option<&Snip20Token>,
+) -> ContractResult<()> {
+    if rake.amount.u128() == 0 {
+        return Ok(());
+    }
+
+    if let Some(token) = token {
+        let mut tokens = load_treasury_tokens(storage)?;
+        if !tokens.contains(token) {
+            tokens.push(token.clone());
+            save_treasury_tokens(storage, &tokens)?;
+        }
+    }
+
+    let mut treasury = load_treasury(storage)?;
+
+    match treasury
+        .iter_mut()
+        .find(|balance| balance.denom == rake.denom)
+    {
+        Some(balance) => balance.amount += rake.amount,
+        None => treasury.push(rake),
+    }
+
+    save_treasury(storage, &treasury)
+}
+
+/// Record the funds, deposited by the player, in the escrow ledger
+/// Stake moves between the pools of the same game, so it leaves the ledger as it is
+/// Token of the deposit is recorded, so the solvency query tells it apart from the native denoms
+fn add_to_escrow<S: Storage>(
+    storage: &mut S,
+    deposit: Coin,
+    token: Option<&Snip20Token>,
+) -> ContractResult<()> {
+    if deposit.amount.u128() == 0 {
+        return Ok(());
+    }
+
+    if let Some(token) = token {
+        let mut tokens = load_escrow_tokens(storage)?;
+        if !tokens.contains(token) {
+            tokens.push(token.clone());
+            save_escrow_tokens(storage, &tokens)?;
+        }
+    }
+
+    let mut escrow = load_escrow(storage)?;
+
+    match escrow
+        .iter_mut()
+        .find(|balance| balance.denom == deposit.denom)
+    {
+        Some(balance) => balance.amount += deposit.amount,
+        None => escrow.push(deposit),
+    }
+
+    save_escrow(storage, &escrow)
+}
+
+/// Release the funds, paid out or refunded to the players (or taken to the treasury), from the escrow ledger
+fn release_from_escrow<S: Storage>(storage: &mut S, amount: Coin) -> ContractResult<()> {
+    if amount.amount.u128() == 0 {
+        return Ok(());
+    }
+
+    let mut escrow = load_escrow(storage)?;
+
+    let balance = escrow
+        .iter_mut()
+        .find(|balance| balance.denom == amount.denom && balance.amount >= amount.amount)
+        .ok_or_else(|| StdError::generic_err(ContractError::InsufficientEscrow {}.to_string()))?;
+    balance.amount = (balance.amount - amount.amount)?;
+
+    escrow.retain(|balance| balance.amount.u128() > 0);
+    save_escrow(storage, &escrow)
+}
+
+/// Withdraw given amount from the DAO treasury to the recipient
+fn withdraw_from_treasury<S: Storage>(
+    storage: &mut S,
+    contract_address: HumanAddr,
+    recipient: HumanAddr,
+    amount: Coin,
+) -> ContractResult<CosmosMsg> {
+    let mut treasury = load_treasury(storage)?;
+
+    let balance = treasury
+        .iter_mut()
+        .find(|balance| balance.denom == amount.denom && balance.amount >= amount.amount)
+        .ok_or_else(|| StdError::generic_err(ContractError::InsufficientTreasury {}.to_string()))?;
+    balance.amount = (balance.amount - amount.amount)?;
+
+    treasury.retain(|balance| balance.amount.u128() > 0);
+    save_treasury(storage, &treasury)?;
+
+    let token = load_treasury_tokens(storage)?
+        .into_iter()
+        .find(|token| token.denom() == amount.denom);
+    payout_msg(token.as_ref(), contract_address, recipient, amount)
+}
+
+/// Get accepted denom with it's limits, unsupported denoms are rejected
+fn accepted_denom<S: Storage>(storage: &S, denom: &str) -> ContractResult<AcceptedDenom> {
+    load_accepted_denoms(storage)?
+        .into_iter()
+        .find(|accepted_denom| accepted_denom.denom == denom)
+        .ok_or_else(|| StdError::generic_err(ContractError::UnsupportedDenom {}.to_string()))
+}
+
+/// Ensure denom is not a whitelisted SNIP-20 token, which can only be bet in the game rooms,
+/// as the queue deposits and the tournament entry fees are sent along with the message
+fn ensure_is_native_denom<S: Storage>(storage: &S, denom: &str) -> ContractResult<()> {
+    if whitelisted_token(storage, denom)?.is_some() {
+        Err(StdError::generic_err(
+            ContractError::NativeDenomRequired {}.to_string(),
         ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Get whitelisted SNIP-20 token, which address is the given denom
+fn whitelisted_token<S: Storage>(storage: &S, denom: &str) -> StdResult<Option<Snip20Token>> {
+    Ok(load_token_whitelist(storage)?
+        .into_iter()
+        .find(|token| token.denom() == denom))
+}
+
+/// Get SNIP-20 token, which address is the given denom, out of the tokens the contract holds
+/// in the escrow or in the treasury, as they are recorded once the funds are added
+fn held_token<S: Storage>(storage: &S, denom: &str) -> StdResult<Option<Snip20Token>> {
+    Ok(load_escrow_tokens(storage)?
+        .into_iter()
+        .chain(load_treasury_tokens(storage)?)
+        .find(|token| token.denom() == denom))
+}
+
+/// Messages, registering the contract to receive the Receive callbacks from each token
+fn register_receive_msgs(
+    tokens: &[Snip20Token],
+    contract_code_hash: String,
+) -> StdResult<Vec<CosmosMsg>> {
+    tokens
+        .iter()
+        .map(|token| token.register_receive_msg(contract_code_hash.clone()))
+        .collect()
+}
+
+/// Execute action of the passed proposal, returns messages to be sent on behalf of the contract
+fn execute_proposal_kind<S: Storage>(
+    storage: &mut S,
+    contract_address: HumanAddr,
+    contract_code_hash: String,
+    kind: ProposalKind,
+) -> ContractResult<Vec<CosmosMsg>> {
+    match kind {
+        ProposalKind::SetScoringTable { scoring_table } => {
+            save_scoring_table(storage, &scoring_table)?
+        }
+        ProposalKind::SetXpTiers { xp_tiers } => save_xp_tiers(storage, &xp_tiers)?,
+        ProposalKind::SetTurnTimeout { blocks } => save_turn_timeout(storage, blocks)?,
+        ProposalKind::SetPendingGameExpiration { blocks } => {
+            save_pending_game_expiration(storage, blocks)?
+        }
+        ProposalKind::SetRake { rake } => save_rake(storage, rake)?,
+        ProposalKind::SetXpScaling { enabled } => save_xp_scaling(storage, enabled)?,
+        ProposalKind::SetAcceptedDenoms { accepted_denoms } => {
+            save_accepted_denoms(storage, &accepted_denoms)?
+        }
+        ProposalKind::SetTokenWhitelist { tokens } => {
+            save_token_whitelist(storage, &tokens)?;
+            return register_receive_msgs(&tokens, contract_code_hash);
+        }
+        ProposalKind::TreasuryWithdraw { recipient, amount } => {
+            return Ok(vec![withdraw_from_treasury(
+                storage,
+                contract_address,
+                recipient,
+                amount,
+            )?]);
+        }
+        ProposalKind::SetAdmin { admin } => save_admin(storage, &admin)?,
+        ProposalKind::SetNftContract { address, code_hash } => {
+            save_nft_address(storage, &address)?;
+            save_nft_code_hash(storage, code_hash)?
+        }
+        ProposalKind::SetGovernanceConfig { config } => save_governance_config(storage, &config)?,
+    }
+
+    Ok(vec![])
+}
+
+/// Update ratings of the game players addresses and NFTs
+/// Returns NFT ratings of the players before the game, indexed by their seats
+fn update_ratings<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    game: &Game,
+    winners: &[Player],
+) -> StdResult<Vec<u32>> {
+    let players_raw = game
+        .seats
+        .iter()
+        .map(|seat| deps.api.canonical_address(&seat.address))
+        .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+
+    let mut player_ratings = players_raw
+        .iter()
+        .map(|player_raw| load_player_rating(&deps.storage, player_raw))
+        .collect::<StdResult<Vec<Rating>>>()?;
+
+    let mut nft_ratings = game
+        .seats
+        .iter()
+        .map(|seat| load_nft_rating(&deps.storage, &seat.nft_id))
+        .collect::<StdResult<Vec<Rating>>>()?;
+
+    let nft_ratings_before = nft_ratings.iter().map(|rating| rating.rating).collect();
+
+    apply_rating_changes(&mut player_ratings, winners);
+    apply_rating_changes(&mut nft_ratings, winners);
+
+    for (player_raw, rating) in players_raw.iter().zip(player_ratings.iter()) {
+        save_player_rating(&mut deps.storage, player_raw, rating)?;
+    }
+
+    for (seat, rating) in game.seats.iter().zip(nft_ratings.iter()) {
+        save_nft_rating(&mut deps.storage, &seat.nft_id, rating)?;
+    }
+
+    Ok(nft_ratings_before)
+}
+
+/// Update stats of the game players and the leaderboards
+/// Players, who opted out, are not listed in the leaderboards
+fn update_leaderboards<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    game_details: &GameDetails,
+    winners: &[Player],
+    nft_xps: &[(Player, u32)],
+) -> StdResult<()> {
+    let game = &game_details.game;
+    let denom = &game.base_bet.denom;
+    let locked = game.locked_per_player().amount.u128();
+    let (payouts, _) = game_details.payouts(winners);
+    let is_draw = game_details.is_draw(winners);
+
+    let net_winnings_kind = LeaderboardKind::NetWinnings {
+        denom: denom.clone(),
+    };
+
+    let mut wins = load_leaderboard(&deps.storage, &LeaderboardKind::Wins)?;
+    let mut net_winnings = load_leaderboard(&deps.storage, &net_winnings_kind)?;
+    let mut xps = load_leaderboard(&deps.storage, &LeaderboardKind::Xp)?;
+    let mut ratings = load_leaderboard(&deps.storage, &LeaderboardKind::Rating)?;
+
+    for (player, seat) in game.seats.iter().enumerate() {
+        let player_raw = deps.api.canonical_address(&seat.address)?;
+
+        let mut stats = load_player_stats(&deps.storage, &player_raw)?;
+        let winner = !is_draw && winners.contains(&player);
+        stats.record_game(winner, denom, locked, payouts[player]);
+        save_player_stats(&mut deps.storage, &player_raw, &stats)?;
+
+        if load_leaderboard_opt_out(&deps.storage, &player_raw)? {
+            continue;
+        }
+
+        let rating = load_player_rating(&deps.storage, &player_raw)?.rating;
+        let entry = |value: u128, nft_id: Option<String>| LeaderboardEntry {
+            player: seat.address.clone(),
+            nft_id,
+            value: Uint128(value),
+        };
+
+        wins.update(entry(stats.wins as u128, None));
+        net_winnings.update(entry(stats.net_winnings(denom).u128(), None));
+        ratings.update(entry(rating as u128, None));
+
+        if let Some((_, xp)) = nft_xps.iter().find(|(winner, _)| *winner == player) {
+            xps.update(entry(*xp as u128, Some(seat.nft_id.clone())));
+        }
+    }
+
+    save_leaderboard(&mut deps.storage, &LeaderboardKind::Wins, &wins)?;
+    save_leaderboard(&mut deps.storage, &net_winnings_kind, &net_winnings)?;
+    save_leaderboard(&mut deps.storage, &LeaderboardKind::Xp, &xps)?;
+    save_leaderboard(&mut deps.storage, &LeaderboardKind::Rating, &ratings)?;
+
+    let mut denoms = load_leaderboard_denoms(&deps.storage)?;
+    if !denoms.contains(denom) {
+        denoms.push(denom.clone());
+        save_leaderboard_denoms(&mut deps.storage, &denoms)?;
+    }
+
+    Ok(())
+}
+
+/// Pay out the completed game, taking the rake to the treasury
+/// Tournament match is played without a stake, it's winner advances in the bracket instead
+fn game_checkout<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    game_details: &GameDetails,
+    winners: &[Player],
+) -> StdResult<Vec<CosmosMsg>> {
+    match &game_details.game.tournament {
+        Some(tournament_match) => advance_tournament(deps, env, tournament_match, winners[0]),
+        None => {
+            let (messages, rake) =
+                game_details.complete_checkout(env.contract.address.clone(), winners)?;
+            release_from_escrow(
+                &mut deps.storage,
+                coin(
+                    game_details.game.game_pool.total_deposits(),
+                    &game_details.game.base_bet.denom,
+                ),
+            )?;
+            add_to_treasury(&mut deps.storage, rake, game_details.game.token.as_ref())?;
+            Ok(messages)
+        }
+    }
+}
+
+/// Record winner of the bracket match, spawning the next round match once it's players are known,
+/// or paying out the prizes, once the final is completed
+fn advance_tournament<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    tournament_match: &TournamentMatch,
+    winner: Player,
+) -> StdResult<Vec<CosmosMsg>> {
+    let tournament_id = tournament_match.tournament_id;
+    let mut tournament_details = load_tournament(&deps.storage, tournament_id)?;
+
+    let next_match = tournament_details.tournament.record_winner(
+        tournament_match.round,
+        tournament_match.index,
+        winner,
+    );
+    if let Some(index) = next_match {
+        spawn_tournament_match(
+            deps,
+            env,
+            tournament_id,
+            &mut tournament_details,
+            tournament_match.round + 1,
+            index,
+        )?;
+    }
+
+    let mut messages = vec![];
+    if tournament_details.tournament.status == TournamentStatus::Finished {
+        let denom = tournament_details.tournament.entry_fee.denom.clone();
+        let (prizes, rake) = tournament_details.tournament.payouts();
+        release_from_escrow(
+            &mut deps.storage,
+            tournament_details.tournament.prize_pool(),
+        )?;
+        add_to_treasury(&mut deps.storage, coin(rake, &denom), None)?;
+
+        for (player, prize) in tournament_details
+            .tournament
+            .players
+            .iter()
+            .zip(prizes.iter())
+        {
+            if *prize > 0 {
+                messages.push(CosmosMsg::Bank(BankMsg::Send {
+                    from_address: env.contract.address.clone(),
+                    to_address: player.address.clone(),
+                    amount: vec![coin(*prize, &denom)],
+                }));
+            }
+        }
+
+        tournament_details.tournament.prizes = prizes
+            .into_iter()
+            .map(|prize| coin(prize, &denom))
+            .collect();
+    }
+
+    save_tournament(&mut deps.storage, tournament_id, &tournament_details)?;
+
+    Ok(messages)
+}
+
+/// Spawn the game of the bracket match, both players of which are known
+fn spawn_tournament_match<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    env: &Env,
+    tournament_id: TournamentId,
+    tournament_details: &mut TournamentDetails,
+    round: u8,
+    index: usize,
+) -> StdResult<()> {
+    let game_id = load_last_game_index(&deps.storage)?;
+    let scoring_table = load_scoring_table(&deps.storage)?;
+
+    let tournament = &tournament_details.tournament;
+    let players = tournament.rounds[round as usize][index].players.clone();
+    let host = &tournament.players[players[0]];
+    let opponent = &tournament.players[players[1]];
+
+    // the stake is paid with the entry fees, so the game is played with the zero base bet and rake
+    let mut game = Game::new(
+        host.address.clone(),
+        host.nft_id.clone(),
+        coin(0, &tournament.entry_fee.denom),
+        false,
+        MIN_PLAYERS,
+        DEFAULT_NUM_OF_DICES,
+        DEFAULT_REROLL_ROUNDS,
+        ScoringMode::default(),
+        scoring_table,
+        0,
+        SINGLE_GAME,
+    );
+    game.created_height = env.block.height;
+    game.tournament = Some(TournamentMatch {
+        tournament_id,
+        round,
+        index,
+    });
+    game.record_action(&env.block);
+    let mut game_details = GameDetails::new(game, tournament_details.secrets[players[0]]);
+
+    // the last seat is taken, so the game starts right away
+    game_details.join(
+        opponent.address.clone(),
+        opponent.nft_id.clone(),
+        tournament_details.secrets[players[1]],
+    );
+
+    // save newly started game
+    save_game(&mut deps.storage, game_id, &game_details)?;
+
+    // increment game index
+    save_last_game_index(&mut deps.storage, &(game_id + 1))?;
+
+    tournament_details.tournament.rounds[round as usize][index].game_id = Some(game_id);
+
+    Ok(())
+}
+
+/// Archive result of the completed game and add it to the history of each player
+fn archive_game_result<S: Storage, A: Api, Q: Querier>(
+    deps: &mut Extern<S, A, Q>,
+    game_id: GameId,
+    result: GameResult,
+) -> StdResult<()> {
+    for player in result.players.iter() {
+        let player_raw = deps.api.canonical_address(&player.address)?;
+        save_player_game(&mut deps.storage, &player_raw, game_id)?;
     }
+
+    save_game_result(&mut deps.storage, game_id, &result)
 }
 
 /// Get `MintDiceNft` handle message from the parameters provided
@@ -635,17 +2722,15 @@ fn get_set_nft_metadata_msg<S: Storage, A: Api, Q: Querier>(
     deps: &Extern<S, A, Q>,
     game_details: &GameDetails,
     winner: Player,
-) -> StdResult<CosmosMsg> {
-    let token_id = match winner {
-        Player::Host => game_details.game.host_player_nft_id.clone(),
-        Player::Joined => game_details.game.joined_player_nft_id.clone(),
-    };
+    xp: u32,
+) -> StdResult<(CosmosMsg, u32)> {
+    let token_id = game_details.game.seats[winner].nft_id.clone();
 
     let winner_nft_metadata = query_nft_info_by_id(deps, token_id.clone())?;
 
     let new_ext = if let NftQueryAnswer::NftInfo { extension, .. } = winner_nft_metadata {
         if let Some(mut ext) = extension {
-            ext.xp += 5;
+            ext.xp += xp;
             ext
         } else {
             return Err(StdError::generic_err("unable to set metadata with uri"));
@@ -656,6 +2741,8 @@ fn get_set_nft_metadata_msg<S: Storage, A: Api, Q: Querier>(
         ));
     };
 
+    let new_xp = new_ext.xp;
+
     let set_metadata_msg = NftHandleMsg::SetMetadata {
         token_id,
         public_metadata: Some(Metadata {
@@ -666,10 +2753,126 @@ fn get_set_nft_metadata_msg<S: Storage, A: Api, Q: Querier>(
         padding: None,
     };
 
-    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+    let set_metadata_msg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: nft_address(&deps.storage)?,
         callback_code_hash: nft_code_hash(&deps.storage)?,
         msg: to_binary(&set_metadata_msg)?,
         send: vec![],
-    }))
+    });
+
+    Ok((set_metadata_msg, new_xp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+
+    #[test]
+    fn permit_signed_by_the_player_is_accepted() {
+        let api = MockApi::new(20);
+
+        assert!(ensure_is_permit_signer(&api, &"alice".into(), &"alice".into()).is_ok());
+    }
+
+    #[test]
+    fn permit_signed_by_another_account_is_rejected() {
+        let api = MockApi::new(20);
+
+        assert_eq!(
+            ensure_is_permit_signer(&api, &"alice".into(), &"mallory".into()),
+            Err(StdError::generic_err(
+                ContractError::PermitNotSignedByPlayer {}.to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn rake_in_a_removed_token_is_withdrawn_with_a_transfer() {
+        let mut storage = MockStorage::new();
+        let token = Snip20Token {
+            address: "token".into(),
+            code_hash: "code_hash".to_string(),
+        };
+        save_treasury(&mut storage, &[]).unwrap();
+        save_token_whitelist(&mut storage, std::slice::from_ref(&token)).unwrap();
+
+        add_to_treasury(&mut storage, coin(30, "token"), Some(&token)).unwrap();
+        add_to_treasury(&mut storage, coin(20, "uscrt"), None).unwrap();
+        save_token_whitelist(&mut storage, &[]).unwrap();
+
+        let msg = withdraw_from_treasury(
+            &mut storage,
+            "contract".into(),
+            "dao".into(),
+            coin(30, "token"),
+        )
+        .unwrap();
+        assert_eq!(msg, token.transfer_msg("dao".into(), Uint128(30)).unwrap());
+
+        let msg = withdraw_from_treasury(
+            &mut storage,
+            "contract".into(),
+            "dao".into(),
+            coin(20, "uscrt"),
+        )
+        .unwrap();
+        assert!(matches!(msg, CosmosMsg::Bank(_)));
+        assert_eq!(load_treasury(&storage).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn solvency_queries_the_balance_of_the_native_denoms_only() {
+        let mut deps = mock_dependencies(20, &[coin(120, "uscrt")]);
+        let bet_token = Snip20Token {
+            address: "bet_token".into(),
+            code_hash: "code_hash".to_string(),
+        };
+        let rake_token = Snip20Token {
+            address: "rake_token".into(),
+            code_hash: "code_hash".to_string(),
+        };
+        save_contract_address(&mut deps.storage, &MOCK_CONTRACT_ADDR.into()).unwrap();
+        save_escrow(&mut deps.storage, &[]).unwrap();
+        save_treasury(&mut deps.storage, &[]).unwrap();
+        add_to_escrow(&mut deps.storage, coin(100, "uscrt"), None).unwrap();
+        add_to_escrow(&mut deps.storage, coin(50, "bet_token"), Some(&bet_token)).unwrap();
+        add_to_treasury(&mut deps.storage, coin(20, "uscrt"), None).unwrap();
+        add_to_treasury(&mut deps.storage, coin(10, "rake_token"), Some(&rake_token)).unwrap();
+
+        let solvency = query_solvency(&deps).unwrap();
+
+        assert!(solvency.solvent);
+        let balances: Vec<(String, Option<Uint128>)> = solvency
+            .denoms
+            .into_iter()
+            .map(|denom| (denom.denom, denom.balance))
+            .collect();
+        assert_eq!(
+            balances,
+            vec![
+                ("uscrt".to_string(), Some(Uint128(120))),
+                ("bet_token".to_string(), None),
+                ("rake_token".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn whitelisted_tokens_are_not_native_denoms() {
+        let mut storage = MockStorage::new();
+        let token = Snip20Token {
+            address: "token".into(),
+            code_hash: "code_hash".to_string(),
+        };
+        save_token_whitelist(&mut storage, &[token]).unwrap();
+
+        assert!(ensure_is_native_denom(&storage, "uscrt").is_ok());
+        assert_eq!(
+            ensure_is_native_denom(&storage, "token"),
+            Err(StdError::generic_err(
+                ContractError::NativeDenomRequired {}.to_string()
+            ))
+        );
+    }
 }
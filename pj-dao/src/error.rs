@@ -24,6 +24,8 @@ pub enum ContractError {
     QueryPlayerNotValid {},
     #[error("NotEnoughXpForTheBaseBet")]
     NotEnoughXpForTheBaseBet {},
+    #[error("NotEnoughXpForShieldedGame")]
+    NotEnoughXpForShieldedGame {},
     #[error("AlreadyHasNFTContract")]
     AlreadyHasNFTContract {},
     #[error("NotAdmin")]
@@ -38,4 +40,90 @@ pub enum ContractError {
     DidNotJoinDao {},
     #[error("PlayerCannotAccessProvidedNft")]
     PlayerCannotAccessProvidedNft {},
+    #[error("PermitNotSignedByPlayer")]
+    PermitNotSignedByPlayer {},
+    #[error("InvalidSecretHash")]
+    InvalidSecretHash {},
+    #[error("InvalidSecret")]
+    InvalidSecret {},
+    #[error("SecretAlreadyRevealed")]
+    SecretAlreadyRevealed {},
+    #[error("SecretDoesNotMatchCommitment")]
+    SecretDoesNotMatchCommitment {},
+    #[error("SecretsNotRevealed")]
+    SecretsNotRevealed {},
+    #[error("NoStalledPlayer")]
+    NoStalledPlayer {},
+    #[error("NotAWaitingPlayer")]
+    NotAWaitingPlayer {},
+    #[error("TurnTimeoutNotReached")]
+    TurnTimeoutNotReached {},
+    #[error("InvalidTurnTimeout")]
+    InvalidTurnTimeout {},
+    #[error("NotAHost")]
+    NotAHost {},
+    #[error("GameExpired")]
+    GameExpired {},
+    #[error("InvalidNumberOfPlayers")]
+    InvalidNumberOfPlayers {},
+    #[error("AlreadyJoinedGame")]
+    AlreadyJoinedGame {},
+    #[error("InvalidNumberOfDices")]
+    InvalidNumberOfDices {},
+    #[error("InvalidNumberOfRerollRounds")]
+    InvalidNumberOfRerollRounds {},
+    #[error("InvalidXpTiers")]
+    InvalidXpTiers {},
+    #[error("InvalidGovernanceConfig")]
+    InvalidGovernanceConfig {},
+    #[error("ProposalIsNotOpen")]
+    ProposalIsNotOpen {},
+    #[error("VotingPeriodNotEnded")]
+    VotingPeriodNotEnded {},
+    #[error("NftAlreadyVoted")]
+    NftAlreadyVoted {},
+    #[error("NoVotingPower")]
+    NoVotingPower {},
+    #[error("InvalidRake")]
+    InvalidRake {},
+    #[error("InsufficientTreasury")]
+    InsufficientTreasury {},
+    #[error("NotInvited")]
+    NotInvited {},
+    #[error("AlreadyQueued")]
+    AlreadyQueued {},
+    #[error("NotQueued")]
+    NotQueued {},
+    #[error("InvalidSeries")]
+    InvalidSeries {},
+    #[error("InvalidTournament")]
+    InvalidTournament {},
+    #[error("TournamentNotInRegistration")]
+    TournamentNotInRegistration {},
+    #[error("AlreadyRegistered")]
+    AlreadyRegistered {},
+    #[error("NotRegistered")]
+    NotRegistered {},
+    #[error("NotAnOrganiser")]
+    NotAnOrganiser {},
+    #[error("NotEnoughXpForTournament")]
+    NotEnoughXpForTournament {},
+    #[error("TokenNotWhitelisted")]
+    TokenNotWhitelisted {},
+    #[error("InvalidReceiveMsg")]
+    InvalidReceiveMsg {},
+    #[error("UnsupportedDenom")]
+    UnsupportedDenom {},
+    #[error("NativeDenomRequired")]
+    NativeDenomRequired {},
+    #[error("BaseBetOutOfRange")]
+    BaseBetOutOfRange {},
+    #[error("InvalidAcceptedDenoms")]
+    InvalidAcceptedDenoms {},
+    #[error("CheckoutMismatch")]
+    CheckoutMismatch {},
+    #[error("InsufficientPlayerPool")]
+    InsufficientPlayerPool {},
+    #[error("InsufficientEscrow")]
+    InsufficientEscrow {},
 }
@@ -1,86 +1,297 @@
 use crate::contract::GameId;
 use crate::error::{ContractError, ContractResult};
-use cosmwasm_std::{coin, BankMsg, Coin, CosmosMsg, HumanAddr, StdError};
+use crate::msg::Snip20Token;
+use crate::tournament::TournamentMatch;
+use cosmwasm_std::{
+    coin, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, HumanAddr, StdError, StdResult,
+};
 use rand::Rng;
 use rand_chacha::ChaChaRng;
 use rand_core::SeedableRng;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::cmp::Ordering;
 
-// total roll rounds
-const TOTAL_ROUNDS: usize = 2;
+// Num of dices each player rolls in the game round
+pub const DEFAULT_NUM_OF_DICES: u8 = 5;
+pub const MIN_NUM_OF_DICES: u8 = 3;
+pub const MAX_NUM_OF_DICES: u8 = 7;
 
-// Max num of dices each player rolls in the game round
-pub const NUM_OF_DICES: usize = 5;
+// Num of reroll rounds following the initial roll
+pub const DEFAULT_REROLL_ROUNDS: u8 = 1;
+pub const MAX_REROLL_ROUNDS: u8 = 3;
+
+// Num of games in the match series, single game by default
+pub const SINGLE_GAME: u8 = 1;
+pub const MIN_SERIES_GAMES: u8 = 3;
+pub const MAX_SERIES_GAMES: u8 = 7;
 
 pub const MIN_DICE_NUMBER: u8 = 1;
 pub const MAX_DICE_NUMBER: u8 = 6;
 
-// (5 dices) x 2 rounds
-type Roll = [u8; NUM_OF_DICES];
-pub type Rolls = [Roll; TOTAL_ROUNDS];
+// Min and max num of players taking seats in the game
+pub const MIN_PLAYERS: u8 = 2;
+pub const MAX_PLAYERS: u8 = 6;
+
+// Rake and other shares are set in basis points
+pub const BASIS_POINTS: u16 = 10_000;
+
+// Seat of the player in the game
+pub type Player = usize;
+
+// Host player always takes the first seat
+pub const HOST: Player = 0;
+
+// (num of dices) x (initial roll + reroll rounds)
+pub type Roll = Vec<u8>;
+pub type Rolls = Vec<Roll>;
 
 // Secret bytes provided by the player
-pub type Secret = [u8; 8];
+pub type Secret = [u8; 32];
+
+// sha256 hash of the secret bytes, player commits to before the secret is revealed
+pub type SecretHash = [u8; 32];
+
+/// Get the hash, player commits to, from the secret bytes
+pub fn secret_hash(secret: &Secret) -> SecretHash {
+    Sha256::digest(secret).into()
+}
 
-// An amount locked per player for a game
-pub fn locked_per_player(base_bet: &Coin) -> Coin {
+/// Get the hash, private game is locked with, from the invite code bytes
+pub fn invite_code_hash(invite_code: &[u8]) -> SecretHash {
+    Sha256::digest(invite_code).into()
+}
+
+/// Message, sending given amount from the contract to the recipient,
+/// SNIP-20 transfer if the amount is in the token, bank send otherwise
+pub fn payout_msg(
+    token: Option<&Snip20Token>,
+    contract_address: HumanAddr,
+    recipient: HumanAddr,
+    amount: Coin,
+) -> StdResult<CosmosMsg> {
+    match token {
+        Some(token) => token.transfer_msg(recipient, amount.amount),
+        None => Ok(CosmosMsg::Bank(BankMsg::Send {
+            from_address: contract_address,
+            to_address: recipient,
+            amount: vec![amount],
+        })),
+    }
+}
+
+// An amount locked per player for a game, enough to roll all the dices in each round of each game
+pub fn locked_per_player(
+    base_bet: &Coin,
+    num_of_dices: u8,
+    reroll_rounds: u8,
+    num_of_games: u8,
+) -> Coin {
     coin(
-        base_bet.amount.u128() * NUM_OF_DICES as u128 * TOTAL_ROUNDS as u128,
+        base_bet.amount.u128()
+            * num_of_dices as u128
+            * (1 + reroll_rounds as u128)
+            * num_of_games as u128,
         &base_bet.denom,
     )
 }
 
-/// Calculate total player points
-pub fn calculate_player_total_points(roll: [u8; NUM_OF_DICES]) -> u8 {
-    // results table [number of equal dices], where index is a number
+/// Get the share of the given amount in basis points
+pub fn basis_points_share(amount: u128, basis_points: u16) -> u128 {
+    amount * basis_points as u128 / BASIS_POINTS as u128
+}
+
+/// Poker dice hand categories, from the lowest to the highest one
+#[derive(
+    Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum HandCategory {
+    Bust,
+    Pair,
+    TwoPairs,
+    ThreeOfAKind,
+    // 1-5
+    LowStraight,
+    // 2-6
+    HighStraight,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
 
-    // 0 - 1
-    // 1 - 2
-    // 2 - 3
-    // 3 - 4
-    // 4 - 5
-    // 5 - 6
+/// Poker dice hand, compared by category first and by kickers next
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Hand {
+    pub category: HandCategory,
+    // dices numbers ordered by the number of equal dices first and by the dice number next
+    pub kickers: Vec<u8>,
+}
 
-    let mut results: [u8; MAX_DICE_NUMBER as usize] = [0; MAX_DICE_NUMBER as usize];
+impl Hand {
+    /// Evaluate poker dice hand of the given roll
+    pub fn new(roll: &[u8]) -> Self {
+        // results table [number of equal dices], where index is a number - 1
+        let mut results: [u8; MAX_DICE_NUMBER as usize] = [0; MAX_DICE_NUMBER as usize];
 
-    for i in 0..MAX_DICE_NUMBER {
         for dice in roll {
-            if dice == (i + 1) {
-                results[i as usize] += 1;
-            }
+            results[(*dice - MIN_DICE_NUMBER) as usize] += 1;
+        }
+
+        let mut kickers = roll.to_vec();
+        kickers.sort_by(|a, b| {
+            let count = |dice: &u8| results[(*dice - MIN_DICE_NUMBER) as usize];
+            count(b).cmp(&count(a)).then(b.cmp(a))
+        });
+
+        let mut counts = results.to_vec();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        let category = match (counts[0], counts[1]) {
+            (5..=u8::MAX, _) => HandCategory::FiveOfAKind,
+            (4, _) => HandCategory::FourOfAKind,
+            (3, 2..=u8::MAX) => HandCategory::FullHouse,
+            _ if results[1..].iter().all(|item| *item >= 1) => HandCategory::HighStraight,
+            _ if results[..5].iter().all(|item| *item >= 1) => HandCategory::LowStraight,
+            (3, _) => HandCategory::ThreeOfAKind,
+            (2, 2) => HandCategory::TwoPairs,
+            (2, _) => HandCategory::Pair,
+            _ => HandCategory::Bust,
+        };
+
+        Self { category, kickers }
+    }
+}
+
+/// Points, each hand category scores in the game
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ScoringTable {
+    pub bust: u8,
+    pub pair: u8,
+    pub two_pairs: u8,
+    pub three_of_a_kind: u8,
+    pub low_straight: u8,
+    pub high_straight: u8,
+    pub full_house: u8,
+    pub four_of_a_kind: u8,
+    pub five_of_a_kind: u8,
+}
+
+impl Default for ScoringTable {
+    fn default() -> Self {
+        Self {
+            // 1 point: 1 pair (or less)
+            bust: 1,
+            pair: 1,
+            // 2 points: 2 pairs
+            two_pairs: 2,
+            // 3 points: 3 of a kind
+            three_of_a_kind: 3,
+            // 4 points: straight (1-5)
+            low_straight: 4,
+            // straight (2-6) is not recognized
+            high_straight: 1,
+            // 4 points: 3 of a kind + 1 pair
+            full_house: 4,
+            // 4 points: 4 of a kind
+            four_of_a_kind: 4,
+            // 5 points: 5 of a kind
+            five_of_a_kind: 5,
+        }
+    }
+}
+
+impl ScoringTable {
+    /// Get points, given hand category scores
+    pub fn points(&self, category: HandCategory) -> u8 {
+        match category {
+            HandCategory::Bust => self.bust,
+            HandCategory::Pair => self.pair,
+            HandCategory::TwoPairs => self.two_pairs,
+            HandCategory::ThreeOfAKind => self.three_of_a_kind,
+            HandCategory::LowStraight => self.low_straight,
+            HandCategory::HighStraight => self.high_straight,
+            HandCategory::FullHouse => self.full_house,
+            HandCategory::FourOfAKind => self.four_of_a_kind,
+            HandCategory::FiveOfAKind => self.five_of_a_kind,
         }
     }
 
-    // 5 points: 5 of 1s
-    if results.iter().any(|item| *item == 5) {
-        5
-    // 4 points: straight (1-5)
-    } else if results.iter().all(|item| *item == 1)
-        // 4 points: 3 of a kind + 1 pair
-        || (results.iter().any(|item| *item == 3) && results.iter().any(|item| *item == 2))
-    {
-        4
-    // 3 points: 3 of a kind
-    } else if results.iter().any(|item| *item == 3) {
-        3
-    // 2 points: 2 pairs
-    } else if results.iter().filter(|item| **item == 2).count() == 2 {
-        2
-    // 1 point: 1 pair (it seems that we can't have less)
-    } else {
-        1
+    /// Get points of the given roll, scored by the best hand category it holds
+    pub fn simple_points(&self, roll: &[u8]) -> u8 {
+        // results table [number of equal dices], where index is a number - 1
+        let mut results: [u8; MAX_DICE_NUMBER as usize] = [0; MAX_DICE_NUMBER as usize];
+
+        for dice in roll {
+            results[(*dice - MIN_DICE_NUMBER) as usize] += 1;
+        }
+
+        let mut counts = results.to_vec();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+
+        // in games with less than 5 dices, all dices of a kind are counted as five of a kind,
+        // and straight takes all the dices
+        let straight_len = roll.len().min(5);
+        let is_straight = |results: &[u8]| results.iter().all(|item| *item >= 1);
+
+        // roll with more than 5 dices can hold both straights, so it scores the best of them
+        let held = [
+            (
+                HandCategory::FiveOfAKind,
+                counts[0] >= 5 || counts[0] as usize == roll.len(),
+            ),
+            (HandCategory::FourOfAKind, counts[0] >= 4),
+            (HandCategory::FullHouse, counts[0] >= 3 && counts[1] >= 2),
+            (
+                HandCategory::HighStraight,
+                is_straight(&results[MAX_DICE_NUMBER as usize - straight_len..]),
+            ),
+            (
+                HandCategory::LowStraight,
+                is_straight(&results[..straight_len]),
+            ),
+            (HandCategory::ThreeOfAKind, counts[0] >= 3),
+            (HandCategory::TwoPairs, counts[1] >= 2),
+            (HandCategory::Pair, counts[0] >= 2),
+        ];
+
+        held.iter()
+            .filter(|(_, is_held)| *is_held)
+            .map(|(category, _)| self.points(*category))
+            .fold(self.bust, u8::max)
+    }
+}
+
+/// The way players hands are scored in the game
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoringMode {
+    // points from the scoring table, ties are broken by dices sum
+    Simple,
+    // poker dice hand categories, ties are broken by kickers
+    ClassicPokerDice,
+}
+
+impl Default for ScoringMode {
+    fn default() -> Self {
+        Self::Simple
+    }
+}
+
+impl ScoringMode {
+    /// Calculate total player points for the given roll
+    pub fn total_points(&self, scoring_table: &ScoringTable, roll: &[u8]) -> u8 {
+        match self {
+            ScoringMode::Simple => scoring_table.simple_points(roll),
+            ScoringMode::ClassicPokerDice => Hand::new(roll).category as u8 + 1,
+        }
     }
 }
 
 /// Reroll chosen dices
-pub fn complete_reroll(
-    rng: &mut ChaChaRng,
-    mut roll: [u8; NUM_OF_DICES],
-    dices: [bool; NUM_OF_DICES],
-) -> [u8; NUM_OF_DICES] {
+pub fn complete_reroll(rng: &mut ChaChaRng, mut roll: Roll, dices: &[bool]) -> Roll {
     for i in 0..roll.len() {
         // reroll
         if dices[i] {
@@ -98,10 +309,23 @@ pub fn complete_reroll(
 pub struct GameDetails {
     // information about the game
     pub game: Game,
-    // secret bytes provided by host player
-    pub host_player_secret: Secret,
-    // secret bytes provided by joined player
-    pub joined_player_secret: Secret,
+    // secrets players committed to, indexed by their seats
+    pub secrets: Vec<PlayerSecret>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct PlayerSecret {
+    // hash of the secret player committed to
+    pub hash: SecretHash,
+    // secret bytes revealed by player
+    pub secret: Option<Secret>,
+}
+
+impl PlayerSecret {
+    pub fn new(hash: SecretHash) -> Self {
+        Self { hash, secret: None }
+    }
 }
 
 impl From<GameDetails> for Game {
@@ -111,11 +335,10 @@ impl From<GameDetails> for Game {
 }
 
 impl GameDetails {
-    pub fn new(game: Game, host_player_secret: Secret) -> GameDetails {
+    pub fn new(game: Game, host_player_secret_hash: SecretHash) -> GameDetails {
         Self {
             game,
-            host_player_secret,
-            joined_player_secret: Secret::default(),
+            secrets: vec![PlayerSecret::new(host_player_secret_hash)],
         }
     }
 
@@ -124,263 +347,428 @@ impl GameDetails {
         &mut self,
         joined_player_address: HumanAddr,
         joined_player_nft_id: String,
-        joined_player_secret: Secret,
+        joined_player_secret_hash: SecretHash,
     ) {
-        // add coins sent by a second player
-        self.game.game_pool.joined_player_pool = locked_per_player(&self.game.base_bet);
+        // add coins sent by a joined player
+        let deposit = self.game.locked_per_player();
+        self.game.game_pool.deposit(deposit);
+
+        self.game
+            .seats
+            .push(Seat::new(joined_player_address, joined_player_nft_id));
+        self.secrets
+            .push(PlayerSecret::new(joined_player_secret_hash));
+
+        // game started, once all the seats are taken
+        // players should reveal their secrets before the first roll
+        if self.game.seats.len() == self.game.max_players as usize {
+            self.game.status = GameStatus::Started;
+        }
+    }
 
-        self.game.joined_player_address = joined_player_address;
-        self.game.joined_player_nft_id = joined_player_nft_id;
-        self.joined_player_secret = joined_player_secret;
+    /// Reveal the secret given player committed to
+    pub fn reveal_secret(&mut self, address: HumanAddr, secret: Secret) -> ContractResult<()> {
+        let player = self.game.ensure_is_player(&address)?;
 
-        // game started
-        self.game.status = GameStatus::Started;
+        let player_secret = &mut self.secrets[player];
+
+        if player_secret.secret.is_some() {
+            return Err(StdError::generic_err(
+                ContractError::SecretAlreadyRevealed {}.to_string(),
+            ));
+        }
+
+        if secret_hash(&secret) != player_secret.hash {
+            return Err(StdError::generic_err(
+                ContractError::SecretDoesNotMatchCommitment {}.to_string(),
+            ));
+        }
+
+        player_secret.secret = Some(secret);
+        self.game.seats[player].revealed = true;
+
+        Ok(())
     }
 
-    // Pick player, the sum of which dices numbers is the biggest
-    fn pick_player_with_biggest_dices_sum(&self) -> Option<Player> {
-        let host_player_dices_sum: u8 = self.game.host_player_rolls[1].iter().sum();
-        let joined_player_dices_sum: u8 = self.game.joined_player_rolls[1].iter().sum();
+    // Seed rng with the revealed secrets of all players (starting from the one, who rolls),
+    // game counter and block data, unknown to the players at the time they committed to their secrets
+    fn rng(&self, game_id: GameId, block: &BlockInfo, entropy: &[u8]) -> ChaChaRng {
+        let num_of_players = self.secrets.len();
+
+        let mut combined_secret = vec![];
+
+        for i in 0..num_of_players {
+            let player = (self.game.roll_turn + i) % num_of_players;
+            combined_secret.extend(self.secrets[player].secret.unwrap_or_default());
+        }
 
-        match host_player_dices_sum.cmp(&joined_player_dices_sum) {
-            Ordering::Greater => Some(Player::Host),
-            Ordering::Less => Some(Player::Joined),
-            // draw
-            Ordering::Equal => None,
+        combined_secret.extend(&game_id.to_be_bytes()); // game counter
+        if let Some(series) = &self.game.series {
+            combined_secret.push(series.games_played); // series game counter
         }
+        combined_secret.extend(&block.height.to_be_bytes());
+        combined_secret.extend(&block.time.to_be_bytes());
+        combined_secret.extend(block.chain_id.as_bytes());
+        combined_secret.extend(entropy);
+
+        let seed: [u8; 32] = Sha256::digest(&combined_secret).into();
+
+        ChaChaRng::from_seed(seed)
     }
 
-    // Determine a winner of the game
-    pub fn determine_a_winner(&self) -> Option<Player> {
-        let host_player_total_points = self.game.host_player_total_points;
-        let joined_player_total_points = self.game.joined_player_total_points;
+    // Pick players, the sum of which final dices numbers is the biggest
+    fn pick_players_with_biggest_dices_sum(&self, players: Vec<Player>) -> Vec<Player> {
+        let dices_sum = |player: &Player| -> u8 {
+            self.game.seats[*player]
+                .rolls
+                .last()
+                .map(|roll| roll.iter().sum())
+                .unwrap_or_default()
+        };
+
+        let biggest_dices_sum = players.iter().map(dices_sum).max().unwrap_or_default();
+
+        players
+            .into_iter()
+            .filter(|player| dices_sum(player) == biggest_dices_sum)
+            .collect()
+    }
 
-        match host_player_total_points.cmp(&joined_player_total_points) {
-            Ordering::Greater => Some(Player::Host),
-            Ordering::Less => Some(Player::Joined),
-            Ordering::Equal => self.pick_player_with_biggest_dices_sum(),
+    // Pick players with the best poker dice hand
+    fn pick_players_with_best_hand(&self, players: Vec<Player>) -> Vec<Player> {
+        let hand = |player: &Player| -> Hand {
+            Hand::new(
+                self.game.seats[*player]
+                    .rolls
+                    .last()
+                    .map(|roll| roll.as_slice())
+                    .unwrap_or_default(),
+            )
+        };
+
+        let best_hand = players.iter().map(hand).max();
+
+        players
+            .into_iter()
+            .filter(|player| Some(hand(player)) == best_hand)
+            .collect()
+    }
+
+    // Determine winners of the game, all players are winners in case of a draw
+    // In simple mode players with equal points are compared by their dices sum,
+    // in classic mode hands are compared by their categories and kickers,
+    // the rest of ties split the pot
+    pub fn determine_winners(&self) -> Vec<Player> {
+        if self.game.scoring_mode == ScoringMode::ClassicPokerDice {
+            return self.pick_players_with_best_hand((0..self.game.seats.len()).collect());
         }
+
+        let biggest_total_points = self
+            .game
+            .seats
+            .iter()
+            .map(|seat| seat.total_points)
+            .max()
+            .unwrap_or_default();
+
+        let players_with_biggest_total_points = (0..self.game.seats.len())
+            .filter(|player| self.game.seats[*player].total_points == biggest_total_points)
+            .collect();
+
+        self.pick_players_with_biggest_dices_sum(players_with_biggest_total_points)
+    }
+
+    /// Check whether all players of the game are winners
+    pub fn is_draw(&self, winners: &[Player]) -> bool {
+        winners.len() == self.game.seats.len()
+    }
+
+    /// Record the finished game of the series and start the next one, unless the series is decided
+    /// Returns winners of the completed game: of the single game or of the whole series
+    pub fn complete_series_game(&mut self, winners: &[Player]) -> Option<Vec<Player>> {
+        let draw = self.is_draw(winners);
+
+        let series = match self.game.series.as_mut() {
+            Some(series) => series,
+            None => return Some(winners.to_vec()),
+        };
+
+        series.record_game(winners, draw);
+        if let Some(series_winners) = series.winners() {
+            return Some(series_winners);
+        }
+
+        // players take turns to roll first, the stake stays in the pool until the series is decided
+        let roll_turn = series.games_played as usize % self.game.seats.len();
+        self.restart(roll_turn);
+
+        None
+    }
+
+    /// Start the game over with the same players, revealed secrets and pools
+    pub fn restart(&mut self, roll_turn: Player) {
+        for seat in self.game.seats.iter_mut() {
+            seat.rolls = vec![];
+            seat.total_points = 0;
+        }
+        self.game.round = 0;
+        self.game.roll_turn = roll_turn;
+        self.game.status = GameStatus::Started;
     }
 
+    /// Get amounts paid out to the players, indexed by their seats, and the rake taken from the stake
+    pub fn payouts(&self, winners: &[Player]) -> (Vec<u128>, u128) {
+        let game_pool = &self.game.game_pool;
+
+        // return money to the users;) less the rake from the amount each of them staked
+        if self.is_draw(winners) {
+            let rakes: Vec<u128> = game_pool
+                .deposits
+                .iter()
+                .zip(game_pool.player_pools.iter())
+                .map(|(deposit, player_pool)| {
+                    basis_points_share(
+                        deposit.amount.u128() - player_pool.amount.u128(),
+                        self.game.rake,
+                    )
+                })
+                .collect();
+
+            let payouts = game_pool
+                .deposits
+                .iter()
+                .zip(rakes.iter())
+                .map(|(deposit, rake)| deposit.amount.u128() - rake)
+                .collect();
+
+            return (payouts, rakes.iter().sum());
+        }
+
+        // return player pools
+        let mut payouts: Vec<u128> = self
+            .game
+            .game_pool
+            .player_pools
+            .iter()
+            .map(|player_pool| player_pool.amount.u128())
+            .collect();
+
+        // split total stake less the rake among the winners, the first one takes the remainder
+        let total_stake = self.game.game_pool.total_stake.amount.u128();
+        let rake = basis_points_share(total_stake, self.game.rake);
+        let share = (total_stake - rake) / winners.len() as u128;
+        let remainder = (total_stake - rake) % winners.len() as u128;
+
+        for (i, winner) in winners.iter().enumerate() {
+            payouts[*winner] += if i == 0 { share + remainder } else { share };
+        }
+
+        (payouts, rake)
+    }
+
+    /// Pay out the game to the players, returns the rake taken from the stake to the treasury
+    /// Payouts and the rake should add up to the players deposits
     pub fn complete_checkout(
         &self,
         contract_address: HumanAddr,
-        winner: Option<Player>,
-    ) -> Vec<CosmosMsg> {
-        let denom = self.game.game_pool.total_stake.denom.to_string();
-        let total_stake = self.game.game_pool.total_stake.amount;
-        let host_player_pool = self.game.game_pool.host_player_pool.amount;
-        let joined_player_pool = self.game.game_pool.joined_player_pool.amount;
-
-        let mut checkout_messages = vec![
-            CosmosMsg::Bank(BankMsg::Send {
-                from_address: contract_address.clone(),
-                to_address: self.game.host_player_address.clone(),
-                amount: vec![Coin {
-                    denom: denom.clone(),
-                    amount: host_player_pool,
-                }],
-            }),
-            CosmosMsg::Bank(BankMsg::Send {
-                from_address: contract_address.clone(),
-                to_address: self.game.joined_player_address.clone(),
-                amount: vec![Coin {
-                    denom: denom.clone(),
-                    amount: joined_player_pool,
-                }],
-            }),
-        ];
+        winners: &[Player],
+    ) -> StdResult<(Vec<CosmosMsg>, Coin)> {
+        let denom = self.game.base_bet.denom.clone();
+        let (payouts, rake) = self.payouts(winners);
+
+        if payouts.iter().sum::<u128>() + rake != self.game.game_pool.total_deposits() {
+            return Err(StdError::generic_err(
+                ContractError::CheckoutMismatch {}.to_string(),
+            ));
+        }
 
-        match winner {
-            Some(Player::Host) => {
-                checkout_messages.push(CosmosMsg::Bank(BankMsg::Send {
-                    from_address: contract_address,
-                    to_address: self.game.host_player_address.clone(),
-                    amount: vec![Coin {
-                        denom,
-                        amount: total_stake,
-                    }],
-                }));
-                checkout_messages
-            }
-            Some(Player::Joined) => {
-                checkout_messages.push(CosmosMsg::Bank(BankMsg::Send {
-                    from_address: contract_address,
-                    to_address: self.game.joined_player_address.clone(),
-                    amount: vec![Coin {
-                        denom,
-                        amount: total_stake,
-                    }],
-                }));
-                checkout_messages
-            }
-            // return money to the users;)
-            None => vec![
-                CosmosMsg::Bank(BankMsg::Send {
-                    from_address: contract_address.clone(),
-                    to_address: self.game.host_player_address.clone(),
-                    amount: vec![Coin {
-                        denom: denom.clone(),
-                        amount: locked_per_player(&self.game.base_bet).amount,
-                    }],
-                }),
-                CosmosMsg::Bank(BankMsg::Send {
-                    from_address: contract_address,
-                    to_address: self.game.joined_player_address.clone(),
-                    amount: vec![Coin {
-                        denom,
-                        amount: locked_per_player(&self.game.base_bet).amount,
-                    }],
-                }),
-            ],
-        }
-    }
-
-    // Add to game stake from the player pool
-    pub fn add_stake(&mut self, number_of_dices: usize, player: Player) {
+        let checkout_messages = self
+            .game
+            .seats
+            .iter()
+            .zip(payouts)
+            .filter(|(_, payout)| *payout > 0)
+            .map(|(seat, payout)| {
+                payout_msg(
+                    self.game.token.as_ref(),
+                    contract_address.clone(),
+                    seat.address.clone(),
+                    coin(payout, &denom),
+                )
+            })
+            .collect::<StdResult<_>>()?;
+
+        Ok((checkout_messages, coin(rake, &denom)))
+    }
+
+    /// Get compact result of the completed game to be archived
+    pub fn result(&self, winners: &[Player], block: &BlockInfo, forfeited: bool) -> GameResult {
+        let denom = &self.game.base_bet.denom;
+        let (payouts, rake) = self.payouts(winners);
+
+        GameResult {
+            players: self
+                .game
+                .seats
+                .iter()
+                .zip(payouts)
+                .map(|(seat, payout)| PlayerResult {
+                    address: seat.address.clone(),
+                    nft_id: seat.nft_id.clone(),
+                    rolls: seat.rolls.clone(),
+                    total_points: seat.total_points,
+                    payout: coin(payout, denom),
+                })
+                .collect(),
+            winners: winners.to_vec(),
+            draw: self.is_draw(winners),
+            series: self.game.series.clone(),
+            forfeited,
+            base_bet: self.game.base_bet.clone(),
+            rake: coin(rake, denom),
+            height: block.height,
+            time: block.time,
+        }
+    }
+
+    /// Return player pools, when the game is cancelled before it started
+    pub fn cancel_checkout(&self, contract_address: HumanAddr) -> StdResult<Vec<CosmosMsg>> {
+        self.game
+            .seats
+            .iter()
+            .zip(self.game.game_pool.player_pools.iter())
+            .filter(|(_, player_pool)| !player_pool.amount.is_zero())
+            .map(|(seat, player_pool)| {
+                payout_msg(
+                    self.game.token.as_ref(),
+                    contract_address.clone(),
+                    seat.address.clone(),
+                    player_pool.clone(),
+                )
+            })
+            .collect()
+    }
+
+    // Add to game stake from the player pool, which can't go negative
+    pub fn add_stake(&mut self, number_of_dices: usize, player: Player) -> ContractResult<()> {
         let base_bet = self.game.base_bet.clone();
 
         let stake = base_bet.amount.u128() * number_of_dices as u128;
+        let player_pool = &mut self.game.game_pool.player_pools[player];
+        let remaining = player_pool
+            .amount
+            .u128()
+            .checked_sub(stake)
+            .ok_or_else(|| {
+                StdError::generic_err(ContractError::InsufficientPlayerPool {}.to_string())
+            })?;
+        *player_pool = coin(remaining, &base_bet.denom);
+
         self.game.game_pool.total_stake = coin(
             self.game.game_pool.total_stake.amount.u128() + stake,
             &base_bet.denom,
         );
 
-        match player {
-            Player::Host => {
-                self.game.game_pool.host_player_pool = coin(
-                    self.game.game_pool.host_player_pool.amount.u128() - stake,
-                    &base_bet.denom,
-                );
-            }
-            Player::Joined => {
-                self.game.game_pool.joined_player_pool = coin(
-                    self.game.game_pool.joined_player_pool.amount.u128() - stake,
-                    &base_bet.denom,
-                );
-            }
-        }
+        Ok(())
     }
 
-    /// Roll dices
-    pub fn roll(&mut self, game_id: GameId) {
-        // Update pool
-        self.add_stake(NUM_OF_DICES, self.game.roll_turn);
+    // Pass roll turn to the next seat
+    fn pass_roll_turn(&mut self) {
+        self.game.roll_turn = (self.game.roll_turn + 1) % self.game.seats.len();
+    }
 
-        let mut combined_secret = vec![];
+    // Move to the next round, once all players completed the current one
+    fn complete_round(&mut self) {
+        let round = self.game.round as usize;
 
-        match self.game.roll_turn {
-            Player::Host => {
-                combined_secret.extend(self.host_player_secret);
-                combined_secret.extend(self.joined_player_secret);
-            }
-            Player::Joined => {
-                combined_secret.extend(self.joined_player_secret);
-                combined_secret.extend(self.host_player_secret);
-            }
-        }
+        if self.game.seats.iter().all(|seat| seat.rolls.len() > round) {
+            self.game.round += 1;
 
-        combined_secret.extend(&game_id.to_be_bytes()); // game counter
+            // Move to the reroll stage, unless all reroll rounds are completed
+            self.game.status = if self.game.round > self.game.reroll_rounds {
+                GameStatus::Finished
+            } else {
+                GameStatus::ReRoll
+            };
+        }
+    }
 
-        let seed: [u8; 32] = Sha256::digest(&combined_secret).into();
+    /// Roll dices
+    pub fn roll(&mut self, game_id: GameId, block: &BlockInfo) -> ContractResult<()> {
+        let num_of_dices = self.game.num_of_dices as usize;
 
-        let mut rng = ChaChaRng::from_seed(seed);
+        // Update pool
+        self.add_stake(num_of_dices, self.game.roll_turn)?;
 
-        let mut roll: [u8; NUM_OF_DICES] = [0; NUM_OF_DICES];
+        let mut rng = self.rng(game_id, block, &[]);
 
         // Generate a random value in the range [low, high).
         // I suppose we need integer values in the range [1, 6]
-        for dice in &mut roll {
-            *dice = rng.gen_range(MIN_DICE_NUMBER, MAX_DICE_NUMBER + 1);
-        }
+        let roll: Roll = (0..num_of_dices)
+            .map(|_| rng.gen_range(MIN_DICE_NUMBER, MAX_DICE_NUMBER + 1))
+            .collect();
+
+        let seat = &mut self.game.seats[self.game.roll_turn];
+        seat.total_points = self
+            .game
+            .scoring_mode
+            .total_points(&self.game.scoring_table, &roll);
+        seat.rolls.push(roll);
 
         // Change roll turn value
-        match self.game.roll_turn {
-            Player::Host => {
-                self.game.host_player_rolls[0] = roll;
-                self.game.host_player_total_points = calculate_player_total_points(roll);
-                self.game.roll_turn = Player::Joined;
-            }
-            Player::Joined => {
-                self.game.joined_player_rolls[0] = roll;
-                self.game.joined_player_total_points = calculate_player_total_points(roll);
-                self.game.roll_turn = Player::Host;
-            }
-        }
+        self.pass_roll_turn();
 
-        // Move to the reroll stage
-        if self.game.host_player_rolls[0] != Roll::default()
-            && self.game.joined_player_rolls[0] != Roll::default()
-        {
-            self.game.status = GameStatus::ReRoll;
-        }
+        self.complete_round();
+
+        Ok(())
     }
 
     /// Reroll chosen dices
     /// false - do not reroll
     /// true - reroll
-    pub fn reroll(&mut self, game_id: GameId, dices: [bool; NUM_OF_DICES]) {
+    pub fn reroll(
+        &mut self,
+        game_id: GameId,
+        block: &BlockInfo,
+        dices: Vec<bool>,
+    ) -> ContractResult<()> {
+        if dices.len() != self.game.num_of_dices as usize {
+            return Err(StdError::generic_err(
+                ContractError::InvalidNumberOfDices {}.to_string(),
+            ));
+        }
+
         // Update pool
         let num_of_dices = dices.iter().filter(|dice| **dice).count();
 
-        self.add_stake(num_of_dices, self.game.roll_turn);
-
-        let mut combined_secret = vec![];
+        self.add_stake(num_of_dices, self.game.roll_turn)?;
 
-        match self.game.roll_turn {
-            Player::Host => {
-                combined_secret.extend(self.host_player_secret);
-                combined_secret.extend(self.joined_player_secret);
-            }
-            Player::Joined => {
-                combined_secret.extend(self.joined_player_secret);
-                combined_secret.extend(self.host_player_secret);
-            }
-        }
+        // num of dices to reroll and the reroll round
+        let mut entropy = num_of_dices.to_be_bytes().to_vec();
+        entropy.push(self.game.round);
+        let mut rng = self.rng(game_id, block, &entropy);
 
-        combined_secret.extend(&game_id.to_be_bytes()); // game counter
+        let scoring_mode = self.game.scoring_mode;
+        let scoring_table = self.game.scoring_table.clone();
+        let seat = &mut self.game.seats[self.game.roll_turn];
+        let last_roll = seat.rolls.last().cloned().unwrap_or_default();
 
-        combined_secret.extend(&num_of_dices.to_be_bytes()); // num of dices to reroll
-
-        let seed: [u8; 32] = Sha256::digest(&combined_secret).into();
-
-        let mut rng = ChaChaRng::from_seed(seed);
-
-        match self.game.roll_turn {
-            Player::Host => {
-                // no dices to reroll
-                if dices.iter().all(|dice| !(*dice)) {
-                    self.game.host_player_rolls[1] = self.game.host_player_rolls[0];
-                } else {
-                    // reroll chosen dices
-                    let reroll = complete_reroll(&mut rng, self.game.host_player_rolls[0], dices);
-                    self.game.host_player_rolls[1] = reroll;
-                    self.game.host_player_total_points = calculate_player_total_points(reroll);
-                }
+        // no dices to reroll
+        if dices.iter().all(|dice| !(*dice)) {
+            seat.rolls.push(last_roll);
+        } else {
+            // reroll chosen dices
+            let reroll = complete_reroll(&mut rng, last_roll, &dices);
+            seat.total_points = scoring_mode.total_points(&scoring_table, &reroll);
+            seat.rolls.push(reroll);
+        }
 
-                self.game.roll_turn = Player::Joined;
-            }
-            Player::Joined => {
-                // no dices to reroll
-                if dices.iter().all(|dice| !(*dice)) {
-                    self.game.joined_player_rolls[1] = self.game.joined_player_rolls[0];
-                } else {
-                    // reroll chosen dices
-                    let reroll = complete_reroll(&mut rng, self.game.joined_player_rolls[0], dices);
-                    self.game.joined_player_rolls[1] = reroll;
-                    self.game.joined_player_total_points = calculate_player_total_points(reroll);
-                }
+        // Change roll turn value
+        self.pass_roll_turn();
 
-                self.game.roll_turn = Player::Host;
-            }
-        }
+        self.complete_round();
 
-        // Move to the Finished stage
-        if self.game.host_player_rolls[1] != Roll::default()
-            && self.game.joined_player_rolls[1] != Roll::default()
-        {
-            self.game.status = GameStatus::Finished;
-        }
+        Ok(())
     }
 
     /// Ensure GameStatus is set to Pending
@@ -405,6 +793,21 @@ impl GameDetails {
         }
     }
 
+    /// Ensure all players revealed their secrets
+    pub fn ensure_secrets_revealed(&self) -> ContractResult<()> {
+        if self
+            .secrets
+            .iter()
+            .any(|player_secret| player_secret.secret.is_none())
+        {
+            Err(StdError::generic_err(
+                ContractError::SecretsNotRevealed {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
     /// Ensure GameStatus is set to Reroll
     pub fn ensure_is_reroll(&self) -> ContractResult<()> {
         if self.game.status.ne(&GameStatus::ReRoll) {
@@ -431,16 +834,16 @@ impl GameDetails {
     pub fn ensure_can_complete_a_game(
         &self,
         address: HumanAddr,
-        winner: Option<Player>,
+        winners: &[Player],
     ) -> ContractResult<()> {
         // if draw reached, anyone can complete a game
-        let can_complete_a_game = match winner {
-            Some(Player::Host) => address == self.game.host_player_address,
-            Some(Player::Joined) => address == self.game.joined_player_address,
-            _ => {
-                address == self.game.host_player_address
-                    || address == self.game.joined_player_address
-            }
+        // in shielded mode players can not see who has won, so any of them can complete a game
+        let can_complete_a_game = if self.game.shielded || self.is_draw(winners) {
+            self.game.is_player(&address)
+        } else {
+            winners
+                .iter()
+                .any(|winner| self.game.seats[*winner].address == address)
         };
 
         if can_complete_a_game {
@@ -452,14 +855,134 @@ impl GameDetails {
         }
     }
 
+    /// Check whether pending game expired, nobody joined it within given number of blocks
+    pub fn is_expired(&self, block: &BlockInfo, pending_game_expiration: Option<u64>) -> bool {
+        match pending_game_expiration {
+            Some(blocks) => {
+                self.game.status == GameStatus::Pending
+                    && block.height >= self.game.last_action_height + blocks
+            }
+            None => false,
+        }
+    }
+
+    /// Ensure pending game did not expire
+    pub fn ensure_is_not_expired(
+        &self,
+        block: &BlockInfo,
+        pending_game_expiration: Option<u64>,
+    ) -> ContractResult<()> {
+        if self.is_expired(block, pending_game_expiration) {
+            Err(StdError::generic_err(
+                ContractError::GameExpired {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ensure given account can cancel a pending game
+    /// Host can cancel it at any time, anyone else only after it expired
+    pub fn ensure_can_cancel(
+        &self,
+        address: &HumanAddr,
+        block: &BlockInfo,
+        pending_game_expiration: Option<u64>,
+    ) -> ContractResult<()> {
+        self.ensure_is_pending()?;
+
+        if *address == self.game.seats[HOST].address
+            || self.is_expired(block, pending_game_expiration)
+        {
+            Ok(())
+        } else {
+            Err(StdError::generic_err(
+                ContractError::NotAHost {}.to_string(),
+            ))
+        }
+    }
+
+    /// Get the players, the game is waiting for
+    /// During the reveal stage these are the players, who did not reveal their secrets yet,
+    /// unless nobody did
+    pub fn stalled_players(&self) -> Vec<Player> {
+        let not_revealed: Vec<Player> = (0..self.secrets.len())
+            .filter(|player| self.secrets[*player].secret.is_none())
+            .collect();
+
+        match self.game.status {
+            GameStatus::Started if not_revealed.len() == self.secrets.len() => vec![],
+            GameStatus::Started if !not_revealed.is_empty() => not_revealed,
+            GameStatus::Started | GameStatus::ReRoll => vec![self.game.roll_turn],
+            _ => vec![],
+        }
+    }
+
+    /// Whether nobody revealed the secret in the started game, so nobody is waiting for the others
+    pub fn is_abandoned(&self) -> bool {
+        self.game.status == GameStatus::Started
+            && self.secrets.iter().all(|secret| secret.secret.is_none())
+    }
+
+    /// Ensure given account can claim a forfeit win, because other players did not act in time
+    /// Anyone can settle the abandoned game as a draw, so the deposits are refunded
+    /// (and the host advances in the tournament)
+    /// Returns the players, who win the game
+    pub fn ensure_can_claim_timeout(
+        &self,
+        address: &HumanAddr,
+        block: &BlockInfo,
+        turn_timeout: u64,
+    ) -> ContractResult<Vec<Player>> {
+        if self.is_abandoned() {
+            self.ensure_turn_timeout_reached(block, turn_timeout)?;
+            return Ok((0..self.game.seats.len()).collect());
+        }
+
+        let stalled_players = self.stalled_players();
+
+        if stalled_players.is_empty() {
+            return Err(StdError::generic_err(
+                ContractError::NoStalledPlayer {}.to_string(),
+            ));
+        }
+
+        let waiting_players: Vec<Player> = (0..self.game.seats.len())
+            .filter(|player| !stalled_players.contains(player))
+            .collect();
+
+        if !waiting_players
+            .iter()
+            .any(|player| self.game.seats[*player].address == *address)
+        {
+            return Err(StdError::generic_err(
+                ContractError::NotAWaitingPlayer {}.to_string(),
+            ));
+        }
+
+        self.ensure_turn_timeout_reached(block, turn_timeout)?;
+
+        Ok(waiting_players)
+    }
+
+    /// Ensure turn timeout passed since the last action in the game
+    fn ensure_turn_timeout_reached(
+        &self,
+        block: &BlockInfo,
+        turn_timeout: u64,
+    ) -> ContractResult<()> {
+        if block.height < self.game.last_action_height.saturating_add(turn_timeout) {
+            Err(StdError::generic_err(
+                ContractError::TurnTimeoutNotReached {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
     /// Ensure given account can make a roll in the game
     pub fn ensure_can_roll(&self, address: HumanAddr) -> ContractResult<()> {
-        let can_roll = match self.game.roll_turn {
-            Player::Host => self.game.host_player_address == address,
-            Player::Joined => self.game.joined_player_address == address,
-        };
-
-        if !can_roll {
+        if self.game.seats[self.game.roll_turn].address != address {
             Err(StdError::generic_err(
                 ContractError::GivenAccountCannotMakeARoll {}.to_string(),
             ))
@@ -476,45 +999,371 @@ pub struct Game {
     pub status: GameStatus,
     // whether the game is shielded
     pub shielded: bool,
-    pub host_player_address: HumanAddr,
-    pub joined_player_address: HumanAddr,
-    pub host_player_nft_id: String,
-    pub joined_player_nft_id: String,
+    // number of seats in the game
+    pub max_players: u8,
+    // num of dices each player rolls
+    pub num_of_dices: u8,
+    // num of reroll rounds following the initial roll
+    pub reroll_rounds: u8,
+    // the way players hands are scored
+    pub scoring_mode: ScoringMode,
+    // scoring table snapshot, taken when the game is created
+    pub scoring_table: ScoringTable,
+    // rake in basis points, taken from the stake to the DAO treasury
+    pub rake: u16,
+    // set for the private games, only invited players can join
+    pub invitation: Option<Invitation>,
+    // set for the best-of-N match series
+    pub series: Option<Series>,
+    // set for the tournament bracket match, played without a stake
+    pub tournament: Option<TournamentMatch>,
+    // current round, 0 stands for the initial roll
+    pub round: u8,
+    // players taking seats in the game, host player takes the first one
+    pub seats: Vec<Seat>,
     // base bet per each dice
     pub base_bet: Coin,
-    // host player roll results (5 dices) x 2 rounds
-    pub host_player_rolls: Rolls,
-    // joined player roll results (5 dices) x 2 rounds
-    pub joined_player_rolls: Rolls,
-    // total game stake
-    pub total_stake: Coin,
+    // set for the bets placed in the SNIP-20 token, base bet denom is it's address
+    pub token: Option<Snip20Token>,
     // game pool
     pub game_pool: GamePool,
-    // host player pool
-    pub host_player_pool: Coin,
-    // host player pool
-    pub joined_player_pool: Coin,
-    // total points amount scored throughout the game by host player
-    pub host_player_total_points: u8,
-    // total points amount scored throughout the game by joined player
-    pub joined_player_total_points: u8,
 
     // who rolls next (default initial player is set to host)
     pub roll_turn: Player,
+
+    // block height, the game room was created at
+    pub created_height: u64,
+    // xp of the host player NFT, when the game room was created
+    pub host_xp: u32,
+
+    // block height of the last player action
+    pub last_action_height: u64,
+    // block time of the last player action
+    pub last_action_time: u64,
 }
 
 impl Game {
-    pub fn new(host_player_address: HumanAddr, host_player_nft_id: String, base_bet: Coin) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        host_player_address: HumanAddr,
+        host_player_nft_id: String,
+        base_bet: Coin,
+        shielded: bool,
+        max_players: u8,
+        num_of_dices: u8,
+        reroll_rounds: u8,
+        scoring_mode: ScoringMode,
+        scoring_table: ScoringTable,
+        rake: u16,
+        num_of_games: u8,
+    ) -> Self {
         Self {
             status: GameStatus::Pending,
-            shielded: false,
-            host_player_address,
-            host_player_nft_id,
-            game_pool: GamePool::new(locked_per_player(&base_bet)),
+            shielded,
+            max_players,
+            num_of_dices,
+            reroll_rounds,
+            scoring_mode,
+            scoring_table,
+            rake,
+            seats: vec![Seat::new(host_player_address, host_player_nft_id)],
+            series: Series::new(num_of_games, max_players),
+            game_pool: GamePool::new(locked_per_player(
+                &base_bet,
+                num_of_dices,
+                reroll_rounds,
+                num_of_games,
+            )),
             base_bet,
             ..Game::default()
         }
     }
+
+    /// Num of games, the game (or the match series) can last
+    pub fn num_of_games(&self) -> u8 {
+        self.series
+            .as_ref()
+            .map(|series| series.best_of)
+            .unwrap_or(SINGLE_GAME)
+    }
+
+    /// An amount locked per player for the game
+    pub fn locked_per_player(&self) -> Coin {
+        locked_per_player(
+            &self.base_bet,
+            self.num_of_dices,
+            self.reroll_rounds,
+            self.num_of_games(),
+        )
+    }
+
+    /// Record block of the last player action
+    pub fn record_action(&mut self, block: &BlockInfo) {
+        self.last_action_height = block.height;
+        self.last_action_time = block.time;
+    }
+
+    /// Get seat of the given account
+    pub fn player(&self, address: &HumanAddr) -> Option<Player> {
+        self.seats.iter().position(|seat| seat.address == *address)
+    }
+
+    /// Check whether given account is one of the game players
+    pub fn is_player(&self, address: &HumanAddr) -> bool {
+        self.player(address).is_some()
+    }
+
+    /// Ensure given account is one of the game players, returns it's seat
+    pub fn ensure_is_player(&self, address: &HumanAddr) -> ContractResult<Player> {
+        self.player(address)
+            .ok_or_else(|| StdError::generic_err(ContractError::NotAPlayer {}.to_string()))
+    }
+
+    /// Ensure given account did not take a seat in the game yet
+    pub fn ensure_is_not_a_player(&self, address: &HumanAddr) -> ContractResult<()> {
+        if self.is_player(address) {
+            Err(StdError::generic_err(
+                ContractError::AlreadyJoinedGame {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check whether the game is restricted to the invited players
+    pub fn is_private(&self) -> bool {
+        self.invitation.is_some()
+    }
+
+    /// Check whether given account can see the game in the lobby
+    pub fn is_visible_to(&self, address: &HumanAddr) -> bool {
+        match &self.invitation {
+            Some(invitation) => self.is_player(address) || invitation.invitees.contains(address),
+            None => true,
+        }
+    }
+
+    /// Ensure given account is invited to the private game, by the address or the invite code
+    pub fn ensure_is_invited(
+        &self,
+        address: &HumanAddr,
+        invite_code: Option<&Binary>,
+    ) -> ContractResult<()> {
+        let invited = match &self.invitation {
+            Some(invitation) => {
+                invitation.invitees.contains(address)
+                    || match (&invitation.code_hash, invite_code) {
+                        (Some(code_hash), Some(invite_code)) => {
+                            invite_code_hash(invite_code.as_slice()) == *code_hash
+                        }
+                        _ => false,
+                    }
+            }
+            None => true,
+        };
+
+        if invited {
+            Ok(())
+        } else {
+            Err(StdError::generic_err(
+                ContractError::NotInvited {}.to_string(),
+            ))
+        }
+    }
+
+    /// Get lightweight summary of the pending game room for the lobby
+    pub fn room_summary(&self, host_xp_tier: u8) -> RoomSummary {
+        RoomSummary {
+            host: self.seats[0].address.clone(),
+            base_bet: self.base_bet.clone(),
+            shielded: self.shielded,
+            max_players: self.max_players,
+            num_of_players: self.seats.len() as u8,
+            num_of_dices: self.num_of_dices,
+            reroll_rounds: self.reroll_rounds,
+            scoring_mode: self.scoring_mode,
+            best_of: self.num_of_games(),
+            host_xp_tier,
+            private: self.is_private(),
+            created_height: self.created_height,
+        }
+    }
+
+    /// Hide rolls and points of the shielded players from the given viewer.
+    /// Players, that are not a viewer, only expose whether they have rerolled via their pools.
+    /// Invitees and invite code hash of the private game are only exposed to it's players.
+    pub fn redacted(mut self, viewer: Option<&HumanAddr>) -> Self {
+        if self.is_private() && !viewer.map(|viewer| self.is_player(viewer)).unwrap_or(false) {
+            self.invitation = Some(Invitation::default());
+        }
+
+        if !self.shielded {
+            return self;
+        }
+
+        for seat in self.seats.iter_mut() {
+            if viewer != Some(&seat.address) {
+                for roll in seat.rolls.iter_mut() {
+                    roll.iter_mut().for_each(|dice| *dice = 0);
+                }
+                seat.total_points = 0;
+            }
+        }
+
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct Seat {
+    pub address: HumanAddr,
+    pub nft_id: String,
+    // whether player revealed the committed secret
+    pub revealed: bool,
+    // player roll results (num of dices) x (initial roll + reroll rounds)
+    pub rolls: Rolls,
+    // total points amount scored throughout the game by player
+    pub total_points: u8,
+}
+
+impl Seat {
+    pub fn new(address: HumanAddr, nft_id: String) -> Self {
+        Self {
+            address,
+            nft_id,
+            ..Seat::default()
+        }
+    }
+}
+
+/// Score of the best-of-N match series
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Series {
+    // max num of games in the series
+    pub best_of: u8,
+    // num of games won by each player, indexed by their seats
+    pub wins: Vec<u8>,
+    // num of games completed, including the drawn ones
+    pub games_played: u8,
+}
+
+impl Series {
+    /// Get the series of the given num of games, if it's not a single game
+    pub fn new(best_of: u8, max_players: u8) -> Option<Self> {
+        if best_of > SINGLE_GAME {
+            Some(Self {
+                best_of,
+                wins: vec![0; max_players as usize],
+                games_played: 0,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Record winners of the completed game, drawn game is not won by anyone
+    pub fn record_game(&mut self, winners: &[Player], draw: bool) {
+        if !draw {
+            for winner in winners {
+                self.wins[*winner] += 1;
+            }
+        }
+        self.games_played += 1;
+    }
+
+    /// Get the series winners, once the majority of games is won or all the games are played
+    pub fn winners(&self) -> Option<Vec<Player>> {
+        let most_wins = self.wins.iter().copied().max().unwrap_or_default();
+
+        if most_wins > self.best_of / 2 || self.games_played >= self.best_of {
+            Some(
+                (0..self.wins.len())
+                    .filter(|player| self.wins[*player] == most_wins)
+                    .collect(),
+            )
+        } else {
+            None
+        }
+    }
+}
+
+/// Restricts who can join the private game
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct Invitation {
+    // addresses, allowed to join the game
+    pub invitees: Vec<HumanAddr>,
+    // sha256 hash of the invite code, allowing anyone who knows it to join the game
+    pub code_hash: Option<SecretHash>,
+}
+
+impl Invitation {
+    /// Get the invitation from the given invitees and invite code hash, if any of them is set
+    pub fn new(invitees: Vec<HumanAddr>, code_hash: Option<SecretHash>) -> Option<Self> {
+        if invitees.is_empty() && code_hash.is_none() {
+            None
+        } else {
+            Some(Self {
+                invitees,
+                code_hash,
+            })
+        }
+    }
+}
+
+/// Summary of the pending game room, listed in the lobby
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RoomSummary {
+    pub host: HumanAddr,
+    pub base_bet: Coin,
+    pub shielded: bool,
+    pub max_players: u8,
+    // num of the seats taken
+    pub num_of_players: u8,
+    pub num_of_dices: u8,
+    pub reroll_rounds: u8,
+    pub scoring_mode: ScoringMode,
+    // num of games in the match series, 1 for the single game
+    pub best_of: u8,
+    // index of the xp tier, host player NFT belonged to, when the room was created
+    pub host_xp_tier: u8,
+    // whether the room is restricted to the invited players
+    pub private: bool,
+    pub created_height: u64,
+}
+
+/// Compact result of the completed game
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct GameResult {
+    // players results, indexed by their seats
+    pub players: Vec<PlayerResult>,
+    pub winners: Vec<Player>,
+    pub draw: bool,
+    // final score of the match series
+    pub series: Option<Series>,
+    // whether the game was completed by the timeout claim
+    pub forfeited: bool,
+    pub base_bet: Coin,
+    // rake taken from the stake to the DAO treasury
+    pub rake: Coin,
+    // block height and time, the game was completed at
+    pub height: u64,
+    pub time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct PlayerResult {
+    pub address: HumanAddr,
+    pub nft_id: String,
+    pub rolls: Rolls,
+    pub total_points: u8,
+    // amount paid out to the player
+    pub payout: Coin,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
@@ -522,20 +1371,32 @@ impl Game {
 pub struct GamePool {
     // an amount already staked in the game
     pub total_stake: Coin,
-    // host player pool
-    pub host_player_pool: Coin,
-    // joined player pool
-    pub joined_player_pool: Coin,
+    // player pools, indexed by their seats
+    pub player_pools: Vec<Coin>,
+    // amounts deposited by the players, indexed by their seats
+    pub deposits: Vec<Coin>,
 }
 
 impl GamePool {
     /// Create a new GamePool
-    fn new(host_player_pool: Coin) -> Self {
-        Self {
-            total_stake: Coin::default(),
-            host_player_pool,
-            joined_player_pool: Coin::default(),
-        }
+    fn new(host_deposit: Coin) -> Self {
+        let mut game_pool = GamePool::default();
+        game_pool.deposit(host_deposit);
+        game_pool
+    }
+
+    /// Record the deposit of the player, taking the next seat
+    fn deposit(&mut self, deposit: Coin) {
+        self.player_pools.push(deposit.clone());
+        self.deposits.push(deposit);
+    }
+
+    /// Get the total amount deposited by the players
+    pub fn total_deposits(&self) -> u128 {
+        self.deposits
+            .iter()
+            .map(|deposit| deposit.amount.u128())
+            .sum()
     }
 }
 
@@ -548,21 +1409,488 @@ pub enum GameStatus {
     Finished,
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum Player {
-    Host,
-    Joined,
-}
-
-impl Default for Player {
+impl Default for GameStatus {
     fn default() -> Self {
-        Self::Host
+        Self::Pending
     }
 }
 
-impl Default for GameStatus {
-    fn default() -> Self {
-        Self::Pending
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // game with the given num of players joined, each of them locks 100uscrt
+    fn started_game(num_of_players: u8, rake: u16) -> GameDetails {
+        let game = Game::new(
+            HumanAddr::from("host"),
+            "nft0".to_string(),
+            coin(10, "uscrt"),
+            false,
+            num_of_players,
+            DEFAULT_NUM_OF_DICES,
+            DEFAULT_REROLL_ROUNDS,
+            ScoringMode::Simple,
+            ScoringTable::default(),
+            rake,
+            SINGLE_GAME,
+        );
+        let mut game_details = GameDetails::new(game, SecretHash::default());
+
+        for player in 1..num_of_players {
+            game_details.join(
+                HumanAddr::from(format!("player{}", player)),
+                format!("nft{}", player),
+                SecretHash::default(),
+            );
+        }
+
+        game_details
+    }
+
+    fn block(height: u64) -> BlockInfo {
+        BlockInfo {
+            height,
+            time: 0,
+            chain_id: String::new(),
+        }
+    }
+
+    fn claim_timeout_error(error: ContractError) -> ContractResult<Vec<Player>> {
+        Err(StdError::generic_err(error.to_string()))
+    }
+
+    // bank send of the uscrt from the contract to the recipient
+    fn bank_send(recipient: &str, amount: u128) -> CosmosMsg {
+        CosmosMsg::Bank(BankMsg::Send {
+            from_address: HumanAddr::from("contract"),
+            to_address: HumanAddr::from(recipient),
+            amount: vec![coin(amount, "uscrt")],
+        })
+    }
+
+    #[test]
+    fn payouts_take_the_rake_from_the_stake() {
+        let mut game_details = started_game(2, 500);
+        game_details.add_stake(5, 0).unwrap();
+        game_details.add_stake(5, 1).unwrap();
+
+        // winner takes the stake less 5% rake on top of the own pool
+        assert_eq!(game_details.payouts(&[1]), (vec![50, 145], 5));
+    }
+
+    #[test]
+    fn payouts_without_the_rake() {
+        let mut game_details = started_game(2, 0);
+        game_details.add_stake(10, 0).unwrap();
+        game_details.add_stake(5, 1).unwrap();
+
+        assert_eq!(game_details.payouts(&[0]), (vec![150, 50], 0));
+    }
+
+    #[test]
+    fn payouts_split_the_stake_among_the_winners() {
+        let mut game_details = started_game(3, 1000);
+        game_details.add_stake(5, 0).unwrap();
+        game_details.add_stake(5, 1).unwrap();
+        game_details.add_stake(5, 2).unwrap();
+
+        // 150 stake less 15 rake is split in two, the first winner takes the remainder
+        assert_eq!(game_details.payouts(&[2, 0]), (vec![117, 50, 118], 15));
+    }
+
+    #[test]
+    fn draw_returns_the_deposits_less_the_rake() {
+        let mut game_details = started_game(2, 500);
+        game_details.add_stake(5, 0).unwrap();
+        game_details.add_stake(10, 1).unwrap();
+
+        // each player pays the rake from the amount they staked
+        assert_eq!(game_details.payouts(&[0, 1]), (vec![98, 95], 7));
+    }
+
+    #[test]
+    fn checkout_skips_the_players_left_without_a_payout() {
+        let mut game_details = started_game(2, 0);
+        game_details.add_stake(10, 0).unwrap();
+        game_details.add_stake(10, 1).unwrap();
+
+        let (messages, rake) = game_details
+            .complete_checkout("contract".into(), &[0])
+            .unwrap();
+
+        assert_eq!(messages, vec![bank_send("host", 200)]);
+        assert_eq!(rake, coin(0, "uscrt"));
+    }
+
+    #[test]
+    fn checkout_not_adding_up_to_the_deposits_is_rejected() {
+        let mut game_details = started_game(2, 500);
+        game_details.add_stake(5, 0).unwrap();
+        game_details.add_stake(5, 1).unwrap();
+        game_details.game.game_pool.total_stake = coin(200, "uscrt");
+
+        assert_eq!(
+            game_details.complete_checkout("contract".into(), &[0]),
+            Err(StdError::generic_err(
+                ContractError::CheckoutMismatch {}.to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn cancel_checkout_returns_the_player_pools() {
+        let mut game_details = started_game(3, 500);
+        game_details.add_stake(10, 1).unwrap();
+
+        // empty pool is skipped, no rake is taken
+        assert_eq!(
+            game_details.cancel_checkout("contract".into()).unwrap(),
+            vec![bank_send("host", 100), bank_send("player2", 100)]
+        );
+    }
+
+    #[test]
+    fn waiting_player_claims_the_win_over_the_player_who_did_not_reveal() {
+        let mut game_details = started_game(2, 0);
+        game_details.game.last_action_height = 100;
+        game_details.secrets[0].secret = Some([1; 32]);
+
+        let claim = |address: &str, height| {
+            game_details.ensure_can_claim_timeout(&address.into(), &block(height), 10)
+        };
+        assert_eq!(
+            claim("host", 109),
+            claim_timeout_error(ContractError::TurnTimeoutNotReached {})
+        );
+        assert_eq!(claim("host", 110), Ok(vec![0]));
+        assert_eq!(
+            claim("player1", 110),
+            claim_timeout_error(ContractError::NotAWaitingPlayer {})
+        );
+        assert_eq!(
+            claim("stranger", 110),
+            claim_timeout_error(ContractError::NotAWaitingPlayer {})
+        );
+    }
+
+    #[test]
+    fn waiting_players_claim_the_win_over_the_player_who_did_not_roll() {
+        let mut game_details = started_game(3, 0);
+        game_details.game.last_action_height = 100;
+        game_details.game.status = GameStatus::ReRoll;
+        game_details.game.roll_turn = 1;
+
+        let claim =
+            |address: &str| game_details.ensure_can_claim_timeout(&address.into(), &block(110), 10);
+        assert_eq!(claim("player2"), Ok(vec![0, 2]));
+        assert_eq!(
+            claim("player1"),
+            claim_timeout_error(ContractError::NotAWaitingPlayer {})
+        );
+    }
+
+    #[test]
+    fn anyone_settles_the_abandoned_game_as_a_draw() {
+        let mut game_details = started_game(2, 0);
+        game_details.game.last_action_height = 100;
+
+        assert!(game_details.is_abandoned());
+        assert_eq!(
+            game_details.ensure_can_claim_timeout(&"stranger".into(), &block(109), 10),
+            claim_timeout_error(ContractError::TurnTimeoutNotReached {})
+        );
+        assert_eq!(
+            game_details.ensure_can_claim_timeout(&"stranger".into(), &block(110), 10),
+            Ok(vec![0, 1])
+        );
+    }
+
+    #[test]
+    fn pending_game_has_no_stalled_player() {
+        let mut pending_game = started_game(2, 0);
+        pending_game.game.status = GameStatus::Pending;
+
+        assert!(!pending_game.is_abandoned());
+        assert_eq!(
+            pending_game.ensure_can_claim_timeout(&"host".into(), &block(1000), 10),
+            claim_timeout_error(ContractError::NoStalledPlayer {})
+        );
+    }
+
+    #[test]
+    fn player_reveals_the_secret_they_committed_to() {
+        let mut game_details = started_game(2, 0);
+        game_details.secrets[0].hash = secret_hash(&[1; 32]);
+
+        assert_eq!(
+            game_details.reveal_secret("host".into(), [2; 32]),
+            Err(StdError::generic_err(
+                ContractError::SecretDoesNotMatchCommitment {}.to_string()
+            ))
+        );
+        assert!(!game_details.game.seats[0].revealed);
+
+        assert_eq!(game_details.reveal_secret("host".into(), [1; 32]), Ok(()));
+        assert_eq!(game_details.secrets[0].secret, Some([1; 32]));
+        assert!(game_details.game.seats[0].revealed);
+    }
+
+    #[test]
+    fn secret_is_revealed_once_by_the_player_only() {
+        let mut game_details = started_game(2, 0);
+        game_details.secrets[0].hash = secret_hash(&[1; 32]);
+        game_details.reveal_secret("host".into(), [1; 32]).unwrap();
+
+        assert_eq!(
+            game_details.reveal_secret("host".into(), [1; 32]),
+            Err(StdError::generic_err(
+                ContractError::SecretAlreadyRevealed {}.to_string()
+            ))
+        );
+        assert_eq!(
+            game_details.reveal_secret("stranger".into(), [1; 32]),
+            Err(StdError::generic_err(
+                ContractError::NotAPlayer {}.to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn private_game_is_joined_by_the_invitees_or_with_the_invite_code() {
+        let mut game = started_game(2, 0).game;
+        game.invitation = Some(Invitation {
+            invitees: vec!["alice".into()],
+            code_hash: Some(invite_code_hash(b"code")),
+        });
+        let not_invited = Err(StdError::generic_err(
+            ContractError::NotInvited {}.to_string(),
+        ));
+
+        assert_eq!(game.ensure_is_invited(&"alice".into(), None), Ok(()));
+        assert_eq!(
+            game.ensure_is_invited(&"bob".into(), Some(&Binary::from(b"code".to_vec()))),
+            Ok(())
+        );
+        assert_eq!(
+            game.ensure_is_invited(&"bob".into(), Some(&Binary::from(b"guess".to_vec()))),
+            not_invited
+        );
+        assert_eq!(game.ensure_is_invited(&"bob".into(), None), not_invited);
+
+        // invite code is not accepted, unless the host set it
+        game.invitation = Some(Invitation {
+            invitees: vec!["alice".into()],
+            code_hash: None,
+        });
+        assert_eq!(
+            game.ensure_is_invited(&"bob".into(), Some(&Binary::from(b"code".to_vec()))),
+            not_invited
+        );
+    }
+
+    #[test]
+    fn public_game_is_joined_by_anyone() {
+        let game = started_game(2, 0).game;
+
+        assert_eq!(game.ensure_is_invited(&"bob".into(), None), Ok(()));
+    }
+
+    // two players game of the series of the given num of games
+    fn started_series(best_of: u8) -> GameDetails {
+        let mut game_details = started_game(2, 0);
+        game_details.game.series = Series::new(best_of, 2);
+        game_details
+    }
+
+    #[test]
+    fn single_game_is_decided_right_away() {
+        let mut game_details = started_game(2, 0);
+
+        assert_eq!(game_details.complete_series_game(&[1]), Some(vec![1]));
+    }
+
+    #[test]
+    fn series_is_decided_by_the_majority_of_games() {
+        let mut game_details = started_series(3);
+        game_details.game.round = 2;
+        game_details.game.status = GameStatus::Finished;
+        game_details.game.seats[0].total_points = 30;
+
+        assert_eq!(game_details.complete_series_game(&[0]), None);
+        // next game starts over with the same players
+        assert_eq!(game_details.game.status, GameStatus::Started);
+        assert_eq!(game_details.game.round, 0);
+        assert_eq!(game_details.game.seats[0].total_points, 0);
+
+        assert_eq!(game_details.complete_series_game(&[0]), Some(vec![0]));
+        assert_eq!(game_details.game.series.unwrap().wins, vec![2, 0]);
+    }
+
+    #[test]
+    fn drawn_games_are_not_won_by_anyone() {
+        let mut game_details = started_series(3);
+
+        assert_eq!(game_details.complete_series_game(&[0, 1]), None);
+        assert_eq!(game_details.complete_series_game(&[0, 1]), None);
+
+        // the last game decides the series, even if it's not the majority of games
+        assert_eq!(game_details.complete_series_game(&[1]), Some(vec![1]));
+        let series = game_details.game.series.unwrap();
+        assert_eq!(series.wins, vec![0, 1]);
+        assert_eq!(series.games_played, 3);
+    }
+
+    #[test]
+    fn players_take_turns_to_roll_first() {
+        let mut game_details = started_series(5);
+
+        game_details.complete_series_game(&[0, 1]);
+        assert_eq!(game_details.game.roll_turn, 1);
+
+        game_details.complete_series_game(&[1]);
+        assert_eq!(game_details.game.roll_turn, 0);
+
+        game_details.complete_series_game(&[0]);
+        assert_eq!(game_details.game.roll_turn, 1);
+    }
+
+    #[test]
+    fn series_ends_tied_once_all_the_games_are_played() {
+        let mut game_details = started_series(3);
+
+        assert_eq!(game_details.complete_series_game(&[0]), None);
+        assert_eq!(game_details.complete_series_game(&[1]), None);
+        assert_eq!(game_details.complete_series_game(&[0, 1]), Some(vec![0, 1]));
+        assert!(game_details.is_draw(&[0, 1]));
+    }
+
+    #[test]
+    fn hand_categories() {
+        let category = |roll: &[u8]| Hand::new(roll).category;
+
+        assert_eq!(category(&[1, 1, 1, 1, 1]), HandCategory::FiveOfAKind);
+        assert_eq!(category(&[2, 5, 2, 2, 2]), HandCategory::FourOfAKind);
+        assert_eq!(category(&[3, 6, 3, 6, 3]), HandCategory::FullHouse);
+        assert_eq!(category(&[6, 2, 5, 3, 4]), HandCategory::HighStraight);
+        assert_eq!(category(&[5, 4, 3, 2, 1]), HandCategory::LowStraight);
+        assert_eq!(category(&[4, 1, 4, 2, 4]), HandCategory::ThreeOfAKind);
+        assert_eq!(category(&[5, 2, 5, 2, 1]), HandCategory::TwoPairs);
+        assert_eq!(category(&[6, 1, 2, 6, 3]), HandCategory::Pair);
+        assert_eq!(category(&[1, 2, 3, 4, 6]), HandCategory::Bust);
+    }
+
+    #[test]
+    fn hands_are_ranked_by_category_first() {
+        let hands: Vec<Hand> = [
+            [6, 5, 4, 3, 1],
+            [1, 1, 2, 3, 4],
+            [1, 1, 2, 2, 3],
+            [1, 1, 1, 2, 3],
+            [1, 2, 3, 4, 5],
+            [2, 3, 4, 5, 6],
+            [1, 1, 1, 2, 2],
+            [1, 1, 1, 1, 2],
+            [1, 1, 1, 1, 1],
+        ]
+        .iter()
+        .map(|roll| Hand::new(roll))
+        .collect();
+
+        for pair in hands.windows(2) {
+            assert!(pair[0] < pair[1], "{:?} < {:?}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn hands_of_the_same_category_are_ranked_by_kickers() {
+        // dices ordered by the number of equal dices first and by the dice number next
+        assert_eq!(Hand::new(&[2, 6, 1, 6, 3]).kickers, vec![6, 6, 3, 2, 1]);
+        assert_eq!(Hand::new(&[5, 2, 5, 2, 6]).kickers, vec![5, 5, 2, 2, 6]);
+        assert_eq!(Hand::new(&[1, 6, 1, 6, 1]).kickers, vec![1, 1, 1, 6, 6]);
+
+        // higher pair wins over the higher kickers
+        assert!(Hand::new(&[5, 5, 4, 3, 2]) < Hand::new(&[6, 6, 1, 2, 3]));
+        // same pair, higher kicker wins
+        assert!(Hand::new(&[6, 6, 4, 2, 1]) < Hand::new(&[6, 6, 5, 2, 1]));
+        // higher three of a kind wins the full house over the higher pair
+        assert!(Hand::new(&[2, 2, 2, 6, 6]) < Hand::new(&[3, 3, 3, 1, 1]));
+        // equal hands, whatever the dices order
+        assert_eq!(Hand::new(&[4, 4, 1, 2, 3]), Hand::new(&[3, 2, 4, 1, 4]));
+    }
+
+    #[test]
+    fn classic_poker_dice_points_follow_hand_categories() {
+        let scoring_table = ScoringTable::default();
+        let points = |roll: &[u8]| ScoringMode::ClassicPokerDice.total_points(&scoring_table, roll);
+
+        assert_eq!(points(&[1, 2, 3, 4, 6]), 1);
+        assert_eq!(points(&[2, 3, 4, 5, 6]), 6);
+        assert_eq!(points(&[1, 1, 1, 1, 1]), 9);
+    }
+
+    #[test]
+    fn simple_points_of_3_dices_roll() {
+        let scoring_table = ScoringTable::default();
+
+        // all dices of a kind are counted as five of a kind
+        assert_eq!(scoring_table.simple_points(&[3, 3, 3]), 5);
+        // straight takes all the dices
+        assert_eq!(scoring_table.simple_points(&[3, 1, 2]), 4);
+        assert_eq!(scoring_table.simple_points(&[6, 4, 5]), 1);
+        assert_eq!(scoring_table.simple_points(&[2, 2, 5]), 1);
+        assert_eq!(scoring_table.simple_points(&[1, 3, 5]), 1);
+    }
+
+    #[test]
+    fn simple_points_of_6_dices_roll() {
+        let scoring_table = ScoringTable::default();
+
+        // roll holding both straights scores the low one, as the high one is not recognized
+        assert_eq!(scoring_table.simple_points(&[6, 5, 4, 3, 2, 1]), 4);
+        assert_eq!(scoring_table.simple_points(&[1, 2, 3, 4, 5, 5]), 4);
+        assert_eq!(scoring_table.simple_points(&[2, 3, 4, 5, 6, 6]), 1);
+        assert_eq!(scoring_table.simple_points(&[6, 6, 6, 6, 6, 1]), 5);
+        assert_eq!(scoring_table.simple_points(&[4, 4, 4, 4, 1, 1]), 4);
+        assert_eq!(scoring_table.simple_points(&[4, 4, 4, 1, 1, 1]), 4);
+        assert_eq!(scoring_table.simple_points(&[4, 4, 1, 1, 2, 3]), 2);
+    }
+
+    #[test]
+    fn simple_points_of_7_dices_roll() {
+        let scoring_table = ScoringTable::default();
+
+        assert_eq!(scoring_table.simple_points(&[1, 2, 3, 4, 5, 6, 6]), 4);
+        assert_eq!(scoring_table.simple_points(&[1, 1, 1, 2, 3, 4, 5]), 4);
+        assert_eq!(scoring_table.simple_points(&[2, 2, 2, 3, 4, 5, 6]), 3);
+        assert_eq!(scoring_table.simple_points(&[5, 5, 5, 5, 5, 5, 2]), 5);
+        assert_eq!(scoring_table.simple_points(&[2, 2, 3, 3, 4, 4, 6]), 2);
+    }
+
+    #[test]
+    fn simple_points_of_both_straights_follow_the_scoring_table() {
+        let scoring_table = ScoringTable {
+            high_straight: 5,
+            ..ScoringTable::default()
+        };
+
+        assert_eq!(scoring_table.simple_points(&[2, 3, 4, 5, 6]), 5);
+        assert_eq!(scoring_table.simple_points(&[1, 2, 3, 4, 5]), 4);
+        assert_eq!(scoring_table.simple_points(&[1, 2, 3, 4, 5, 6]), 5);
+        assert_eq!(scoring_table.simple_points(&[1, 2, 3, 4, 5, 6, 1]), 5);
+        assert_eq!(scoring_table.simple_points(&[4, 5, 6]), 5);
+    }
+
+    #[test]
+    fn simple_points_of_5_dices_roll() {
+        let scoring_table = ScoringTable::default();
+
+        assert_eq!(scoring_table.simple_points(&[1, 1, 1, 1, 1]), 5);
+        assert_eq!(scoring_table.simple_points(&[6, 6, 6, 6, 1]), 4);
+        assert_eq!(scoring_table.simple_points(&[6, 6, 6, 1, 1]), 4);
+        assert_eq!(scoring_table.simple_points(&[5, 4, 3, 2, 1]), 4);
+        assert_eq!(scoring_table.simple_points(&[6, 5, 4, 3, 2]), 1);
+        assert_eq!(scoring_table.simple_points(&[6, 6, 6, 1, 2]), 3);
+        assert_eq!(scoring_table.simple_points(&[6, 6, 1, 1, 2]), 2);
+        assert_eq!(scoring_table.simple_points(&[6, 6, 1, 3, 2]), 1);
     }
 }
@@ -0,0 +1,289 @@
+use crate::error::{ContractError, ContractResult};
+use crate::game::ScoringTable;
+use crate::msg::{AcceptedDenom, Snip20Token, XpTier};
+use cosmwasm_std::{BlockInfo, Coin, HumanAddr, StdError};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub type ProposalId = u64;
+
+// Default num of blocks, proposal can be voted for (~1 week)
+pub const DEFAULT_VOTING_PERIOD: u64 = 100_800;
+// Default min total weight of the votes, proposal should gather
+pub const DEFAULT_QUORUM: u64 = 100;
+// Default min percentage of `yes` votes among `yes` and `no` ones, proposal should gather to pass
+pub const DEFAULT_THRESHOLD: u8 = 50;
+
+/// Get vote weight of the dice NFT with the given xp
+pub fn vote_weight(xp: u32) -> u64 {
+    // NFTs are minted for free, so only the ones, that played, have a voice
+    xp as u64
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct GovernanceConfig {
+    // num of blocks, proposal can be voted for
+    pub voting_period: u64,
+    // min total weight of the votes, proposal should gather
+    pub quorum: u64,
+    // min percentage of `yes` votes among `yes` and `no` ones, proposal should exceed to pass
+    pub threshold: u8,
+}
+
+impl Default for GovernanceConfig {
+    fn default() -> Self {
+        Self {
+            voting_period: DEFAULT_VOTING_PERIOD,
+            quorum: DEFAULT_QUORUM,
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+}
+
+impl GovernanceConfig {
+    /// Ensure proposals can be voted for and passed with the given config
+    pub fn ensure_is_correct(&self) -> ContractResult<()> {
+        if self.voting_period == 0 || self.threshold >= 100 {
+            Err(StdError::generic_err(
+                ContractError::InvalidGovernanceConfig {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Action, executed once proposal passed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalKind {
+    SetScoringTable {
+        scoring_table: ScoringTable,
+    },
+    SetXpTiers {
+        xp_tiers: Vec<XpTier>,
+    },
+    SetTurnTimeout {
+        blocks: u64,
+    },
+    SetPendingGameExpiration {
+        blocks: Option<u64>,
+    },
+    SetRake {
+        rake: u16,
+    },
+    TreasuryWithdraw {
+        recipient: HumanAddr,
+        amount: Coin,
+    },
+    SetXpScaling {
+        enabled: bool,
+    },
+    SetAcceptedDenoms {
+        accepted_denoms: Vec<AcceptedDenom>,
+    },
+    SetTokenWhitelist {
+        tokens: Vec<Snip20Token>,
+    },
+    SetAdmin {
+        admin: HumanAddr,
+    },
+    SetNftContract {
+        address: HumanAddr,
+        code_hash: String,
+    },
+    SetGovernanceConfig {
+        config: GovernanceConfig,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes,
+    No,
+    Abstain,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    // proposal can be voted for
+    Open,
+    // proposal passed and its action is executed
+    Executed,
+    // proposal did not reach the quorum or the threshold
+    Rejected,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct Tally {
+    pub yes: u64,
+    pub no: u64,
+    pub abstain: u64,
+}
+
+impl Tally {
+    /// Total weight of the votes cast
+    pub fn total(&self) -> u64 {
+        self.yes + self.no + self.abstain
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Proposal {
+    pub proposer: HumanAddr,
+    pub description: String,
+    pub kind: ProposalKind,
+    pub status: ProposalStatus,
+    // block height, proposal was submitted at
+    pub start_height: u64,
+    // block height, voting period ends at
+    pub end_height: u64,
+    // quorum and threshold, proposal was submitted with
+    pub quorum: u64,
+    pub threshold: u8,
+    pub tally: Tally,
+}
+
+impl Proposal {
+    pub fn new(
+        proposer: HumanAddr,
+        description: String,
+        kind: ProposalKind,
+        block: &BlockInfo,
+        config: &GovernanceConfig,
+    ) -> Self {
+        Self {
+            proposer,
+            description,
+            kind,
+            status: ProposalStatus::Open,
+            start_height: block.height,
+            end_height: block.height + config.voting_period,
+            quorum: config.quorum,
+            threshold: config.threshold,
+            tally: Tally::default(),
+        }
+    }
+
+    /// Add a vote with the given weight to the tally
+    pub fn vote(&mut self, vote: VoteOption, weight: u64) {
+        match vote {
+            VoteOption::Yes => self.tally.yes += weight,
+            VoteOption::No => self.tally.no += weight,
+            VoteOption::Abstain => self.tally.abstain += weight,
+        }
+    }
+
+    /// Check whether proposal reached the quorum and the threshold
+    pub fn is_passed(&self) -> bool {
+        self.tally.total() >= self.quorum
+            && self.tally.yes as u128 * 100
+                > self.threshold as u128 * (self.tally.yes + self.tally.no) as u128
+    }
+
+    /// Ensure proposal can be voted for
+    pub fn ensure_is_open(&self, block: &BlockInfo) -> ContractResult<()> {
+        if self.status != ProposalStatus::Open || block.height >= self.end_height {
+            Err(StdError::generic_err(
+                ContractError::ProposalIsNotOpen {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ensure voting period has ended and proposal was not executed or rejected yet
+    pub fn ensure_can_execute(&self, block: &BlockInfo) -> ContractResult<()> {
+        if self.status != ProposalStatus::Open {
+            Err(StdError::generic_err(
+                ContractError::ProposalIsNotOpen {}.to_string(),
+            ))
+        } else if block.height < self.end_height {
+            Err(StdError::generic_err(
+                ContractError::VotingPeriodNotEnded {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proposal(quorum: u64, threshold: u8) -> Proposal {
+        let block = BlockInfo {
+            height: 1,
+            time: 0,
+            chain_id: String::new(),
+        };
+        let config = GovernanceConfig {
+            voting_period: 10,
+            quorum,
+            threshold,
+        };
+        Proposal::new(
+            "proposer".into(),
+            String::new(),
+            ProposalKind::SetRake { rake: 100 },
+            &block,
+            &config,
+        )
+    }
+
+    #[test]
+    fn nfts_without_xp_have_no_vote_weight() {
+        assert_eq!(vote_weight(0), 0);
+        assert_eq!(vote_weight(120), 120);
+    }
+
+    #[test]
+    fn proposal_passes_with_the_quorum_and_the_threshold() {
+        let mut proposal = proposal(100, 50);
+        proposal.vote(VoteOption::Yes, 60);
+        proposal.vote(VoteOption::No, 40);
+
+        assert!(proposal.is_passed());
+    }
+
+    #[test]
+    fn proposal_needs_the_yes_votes_to_exceed_the_threshold() {
+        let mut proposal = proposal(100, 50);
+        proposal.vote(VoteOption::Yes, 50);
+        proposal.vote(VoteOption::No, 50);
+
+        // half of the votes is not over the 50% threshold
+        assert!(!proposal.is_passed());
+
+        proposal.vote(VoteOption::Yes, 1);
+        assert!(proposal.is_passed());
+    }
+
+    #[test]
+    fn proposal_needs_the_votes_to_reach_the_quorum() {
+        let mut proposal = proposal(100, 50);
+        proposal.vote(VoteOption::Yes, 60);
+        proposal.vote(VoteOption::Abstain, 39);
+
+        assert!(!proposal.is_passed());
+
+        // abstain votes count towards the quorum only
+        proposal.vote(VoteOption::Abstain, 1);
+        assert!(proposal.is_passed());
+    }
+
+    #[test]
+    fn proposal_without_yes_and_no_votes_is_not_passed() {
+        let mut proposal = proposal(0, 0);
+        assert!(!proposal.is_passed());
+
+        proposal.vote(VoteOption::Abstain, 10);
+        assert!(!proposal.is_passed());
+    }
+}
@@ -0,0 +1,129 @@
+use cosmwasm_std::{HumanAddr, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Max num of entries, kept in each leaderboard
+pub const MAX_LEADERBOARD_SIZE: usize = 100;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardKind {
+    // num of games won by the player address
+    Wins,
+    // net winnings of the player address in the given denom
+    NetWinnings { denom: String },
+    // xp of the dice NFT
+    Xp,
+    // rating of the player address
+    Rating,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct LeaderboardEntry {
+    pub player: HumanAddr,
+    // set for the dice NFTs leaderboards
+    pub nft_id: Option<String>,
+    pub value: Uint128,
+}
+
+impl LeaderboardEntry {
+    /// Check whether both entries belong to the same NFT (even if it changed the owner) or player
+    fn is_same(&self, other: &LeaderboardEntry) -> bool {
+        match (&self.nft_id, &other.nft_id) {
+            (None, None) => self.player == other.player,
+            (nft_id, other_nft_id) => nft_id == other_nft_id,
+        }
+    }
+}
+
+/// Leaderboard entries sorted by their values, the biggest one goes first
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    /// Insert or replace the entry of the same player (or NFT), keeping leaderboard sorted and capped
+    pub fn update(&mut self, entry: LeaderboardEntry) {
+        self.entries.retain(|item| !item.is_same(&entry));
+
+        if entry.value.is_zero() {
+            return;
+        }
+
+        // entries with equal values keep their order, the earlier one stays higher
+        let position = self
+            .entries
+            .iter()
+            .position(|item| item.value < entry.value)
+            .unwrap_or(self.entries.len());
+
+        self.entries.insert(position, entry);
+        self.entries.truncate(MAX_LEADERBOARD_SIZE);
+    }
+
+    /// Remove all the entries of the given player
+    pub fn remove(&mut self, player: &HumanAddr) {
+        self.entries.retain(|item| item.player != *player);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Winnings {
+    pub denom: String,
+    // total amount won and lost by the player throughout the games
+    pub won: Uint128,
+    pub lost: Uint128,
+}
+
+impl Winnings {
+    /// Net winnings, if player won more than lost
+    pub fn net(&self) -> Uint128 {
+        Uint128(self.won.u128().saturating_sub(self.lost.u128()))
+    }
+}
+
+/// Player address results, leaderboards are built from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct PlayerStats {
+    pub wins: u32,
+    pub winnings: Vec<Winnings>,
+}
+
+impl PlayerStats {
+    /// Record the game result, given player locked and got paid out the given amounts
+    pub fn record_game(&mut self, winner: bool, denom: &str, locked: u128, payout: u128) {
+        if winner {
+            self.wins += 1;
+        }
+
+        let index = match self.winnings.iter().position(|item| item.denom == denom) {
+            Some(index) => index,
+            None => {
+                self.winnings.push(Winnings {
+                    denom: denom.to_string(),
+                    won: Uint128::zero(),
+                    lost: Uint128::zero(),
+                });
+                self.winnings.len() - 1
+            }
+        };
+
+        let winnings = &mut self.winnings[index];
+        winnings.won = Uint128(winnings.won.u128() + payout.saturating_sub(locked));
+        winnings.lost = Uint128(winnings.lost.u128() + locked.saturating_sub(payout));
+    }
+
+    /// Net winnings in the given denom
+    pub fn net_winnings(&self, denom: &str) -> Uint128 {
+        self.winnings
+            .iter()
+            .find(|item| item.denom == denom)
+            .map(Winnings::net)
+            .unwrap_or_default()
+    }
+}
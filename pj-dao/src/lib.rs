@@ -1,8 +1,13 @@
 pub mod contract;
 pub mod error;
 pub mod game;
+pub mod governance;
+pub mod leaderboard;
+pub mod matchmaking;
 pub mod msg;
+pub mod rating;
 pub mod state;
+pub mod tournament;
 
 // IT seems that this functions does not support custom error. ;(
 
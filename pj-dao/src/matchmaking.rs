@@ -0,0 +1,168 @@
+use crate::game::SecretHash;
+use cosmwasm_std::{Coin, HumanAddr};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Max xp and rating differences, player accepts the opponent with
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct MatchWindow {
+    // max difference of the dice NFTs xp
+    pub max_xp_difference: Option<u32>,
+    // max difference of the player addresses ratings
+    pub max_rating_difference: Option<u32>,
+}
+
+impl MatchWindow {
+    /// Check whether xp and rating differences fit into the window
+    pub fn accepts(&self, xp_difference: u32, rating_difference: u32) -> bool {
+        self.max_xp_difference
+            .map(|max_xp_difference| xp_difference <= max_xp_difference)
+            .unwrap_or(true)
+            && self
+                .max_rating_difference
+                .map(|max_rating_difference| rating_difference <= max_rating_difference)
+                .unwrap_or(true)
+    }
+}
+
+/// Player waiting for the match
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct QueueEntry {
+    pub player: HumanAddr,
+    pub nft_id: String,
+    // hash of the secret player committed to
+    pub secret_hash: SecretHash,
+    // xp of the dice NFT and rating of the player address, when player queued
+    pub xp: u32,
+    pub rating: u32,
+    pub window: MatchWindow,
+    // amount locked by the player, refunded if player leaves the queue
+    pub deposit: Coin,
+}
+
+impl QueueEntry {
+    /// Check whether both players accept each other as the opponents
+    pub fn matches(&self, other: &QueueEntry) -> bool {
+        let xp_difference = self.xp.max(other.xp) - self.xp.min(other.xp);
+        let rating_difference = self.rating.max(other.rating) - self.rating.min(other.rating);
+
+        self.player != other.player
+            && self.window.accepts(xp_difference, rating_difference)
+            && other.window.accepts(xp_difference, rating_difference)
+    }
+}
+
+/// Players waiting for the match at the same base bet, the earliest one goes first
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct MatchQueue {
+    pub entries: Vec<QueueEntry>,
+}
+
+impl MatchQueue {
+    /// Check whether given account waits in the queue
+    pub fn contains(&self, player: &HumanAddr) -> bool {
+        self.entries.iter().any(|entry| entry.player == *player)
+    }
+
+    /// Take the earliest queued player, matching the given entry, out of the queue
+    pub fn take_match(&mut self, entry: &QueueEntry) -> Option<QueueEntry> {
+        self.entries
+            .iter()
+            .position(|queued| queued.matches(entry))
+            .map(|position| self.entries.remove(position))
+    }
+
+    /// Take the entry of the given account out of the queue
+    pub fn remove(&mut self, player: &HumanAddr) -> Option<QueueEntry> {
+        self.entries
+            .iter()
+            .position(|entry| entry.player == *player)
+            .map(|position| self.entries.remove(position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coin;
+
+    fn entry(player: &str, xp: u32, rating: u32, window: MatchWindow) -> QueueEntry {
+        QueueEntry {
+            player: HumanAddr::from(player),
+            nft_id: format!("{}_nft", player),
+            secret_hash: SecretHash::default(),
+            xp,
+            rating,
+            window,
+            deposit: coin(100, "uscrt"),
+        }
+    }
+
+    fn window(max_xp_difference: Option<u32>, max_rating_difference: Option<u32>) -> MatchWindow {
+        MatchWindow {
+            max_xp_difference,
+            max_rating_difference,
+        }
+    }
+
+    #[test]
+    fn window_accepts_the_differences_up_to_the_max() {
+        assert!(MatchWindow::default().accepts(u32::MAX, u32::MAX));
+
+        let window = window(Some(10), Some(100));
+        assert!(window.accepts(10, 100));
+        assert!(!window.accepts(11, 100));
+        assert!(!window.accepts(10, 101));
+    }
+
+    #[test]
+    fn earliest_matching_player_is_taken_out_of_the_queue() {
+        let mut queue = MatchQueue {
+            entries: vec![
+                entry("alice", 0, 1200, window(Some(5), None)),
+                entry("bob", 20, 1200, MatchWindow::default()),
+                entry("carol", 25, 1300, MatchWindow::default()),
+            ],
+        };
+
+        // alice does not accept the opponent with 20 more xp
+        let taken = queue.take_match(&entry("dave", 20, 1200, MatchWindow::default()));
+
+        assert_eq!(taken.map(|entry| entry.player), Some("bob".into()));
+        assert!(queue.contains(&"alice".into()));
+        assert!(!queue.contains(&"bob".into()));
+        assert!(queue.contains(&"carol".into()));
+    }
+
+    #[test]
+    fn both_players_should_accept_each_other() {
+        let mut queue = MatchQueue {
+            entries: vec![entry("alice", 0, 1200, MatchWindow::default())],
+        };
+
+        // dave does not accept the opponent, rated 150 less
+        let dave = entry("dave", 0, 1350, window(None, Some(100)));
+        assert_eq!(queue.take_match(&dave), None);
+        assert!(queue.contains(&"alice".into()));
+    }
+
+    #[test]
+    fn player_is_not_matched_with_themselves() {
+        let mut queue = MatchQueue {
+            entries: vec![entry("alice", 0, 1200, MatchWindow::default())],
+        };
+
+        assert_eq!(
+            queue.take_match(&entry("alice", 0, 1200, MatchWindow::default())),
+            None
+        );
+        assert_eq!(
+            queue.remove(&"alice".into()).map(|entry| entry.player),
+            Some("alice".into())
+        );
+        assert!(queue.entries.is_empty());
+    }
+}
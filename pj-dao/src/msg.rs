@@ -1,18 +1,42 @@
 #![allow(clippy::large_enum_variant)]
 
 use crate::error::{ContractError, ContractResult};
-use crate::game::{GameDetails, GameStatus, NUM_OF_DICES};
-use cosmwasm_std::{Binary, Coin, HumanAddr, StdError};
+use crate::game::{GameDetails, GameStatus, RoomSummary, ScoringMode, ScoringTable, BASIS_POINTS};
+use crate::governance::{GovernanceConfig, ProposalId, ProposalKind, VoteOption};
+use crate::leaderboard::LeaderboardKind;
+use crate::matchmaking::MatchWindow;
+use crate::tournament::TournamentId;
+use cosmwasm_std::{Binary, Coin, CosmosMsg, HumanAddr, StdError, StdResult, Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::contract::{GameId, Secret};
+use crate::contract::GameId;
 use secret_toolkit::permit::Permit;
+use secret_toolkit::snip20::{register_receive_msg, transfer_msg};
+
+// Block size, messages to the SNIP-20 token contracts are padded to
+pub const BLOCK_SIZE: usize = 256;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InitMsg {
     pub nft_code_id: u64,
     pub nft_code_hash: String,
+    // number of blocks, player has to make a move in, before the opponent can claim a win
+    pub turn_timeout: Option<u64>,
+    // number of blocks, pending game expires after, if nobody joined it
+    pub pending_game_expiration: Option<u64>,
+    // points, each hand category scores in the game
+    pub scoring_table: Option<ScoringTable>,
+    // NFT privileges depending on it's xp
+    pub xp_tiers: Option<Vec<XpTier>>,
+    // voting period, quorum and threshold of the proposals
+    pub governance: Option<GovernanceConfig>,
+    // rake in basis points, taken from the stake of each game to the DAO treasury
+    pub rake: Option<u16>,
+    // whether NFT xp gain scales with the rating difference of the winner and the opponents
+    pub xp_scaling: Option<bool>,
+    // denoms, bets can be placed in, with their base bet limits (uscrt only by default)
+    pub accepted_denoms: Option<Vec<AcceptedDenom>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -23,25 +47,156 @@ pub enum HandleMsg {
     CreateNewGameRoom {
         nft_id: String,
         base_bet: Coin,
-        secret: Secret,
+        // sha256 hash of the 32 secret bytes, revealed before the first roll
+        secret_hash: Binary,
         permit: Permit,
+        // hide players rolls until the game is ended
+        shielded: Option<bool>,
+        // number of seats in the game, from 2 (default) to 6
+        max_players: Option<u8>,
+        // num of dices each player rolls, from 3 to 7 (5 by default)
+        num_of_dices: Option<u8>,
+        // num of reroll rounds following the initial roll, from 0 to 3 (1 by default)
+        reroll_rounds: Option<u8>,
+        // addresses, allowed to join the private game
+        invitees: Option<Vec<HumanAddr>>,
+        // sha256 hash of the invite code, allowing anyone who knows it to join the private game
+        invite_code_hash: Option<Binary>,
+        // the way players hands are scored, simple (default) or classic poker dice
+        scoring_mode: Option<ScoringMode>,
+        // num of games in the match series of two players, 3, 5 or 7 (single game by default)
+        best_of: Option<u8>,
     },
     JoinGame {
         nft_id: String,
         game_id: GameId,
-        secret: Secret,
+        // sha256 hash of the 32 secret bytes, revealed before the first roll
+        secret_hash: Binary,
         permit: Permit,
+        // code, the private game is locked with, unless player is invited by the address
+        invite_code: Option<Binary>,
+    },
+    RevealSecret {
+        game_id: GameId,
+        // 32 secret bytes, sha256 hash of which player committed to
+        secret: Binary,
     },
     Roll {
         game_id: GameId,
     },
     ReRoll {
         game_id: GameId,
-        dices: [bool; NUM_OF_DICES],
+        dices: Vec<bool>,
     },
     EndGame {
         game_id: GameId,
     },
+    ClaimTimeout {
+        game_id: GameId,
+    },
+    CancelGame {
+        game_id: GameId,
+    },
+    SetTurnTimeout {
+        blocks: u64,
+    },
+    SetPendingGameExpiration {
+        blocks: Option<u64>,
+    },
+    // applies to the games created afterwards
+    SetScoringTable {
+        scoring_table: ScoringTable,
+    },
+    SetXpTiers {
+        xp_tiers: Vec<XpTier>,
+    },
+    // applies to the games created afterwards
+    SetRake {
+        rake: u16,
+    },
+    TreasuryWithdraw {
+        recipient: HumanAddr,
+        amount: Coin,
+    },
+    SetXpScaling {
+        enabled: bool,
+    },
+    // denoms, bets can be placed in, applies to the games created afterwards
+    SetAcceptedDenoms {
+        accepted_denoms: Vec<AcceptedDenom>,
+    },
+    // SNIP-20 tokens, accepted as the bet asset, applies to the games created afterwards
+    SetTokenWhitelist {
+        tokens: Vec<Snip20Token>,
+    },
+    // SNIP-20 callback, bet tokens are sent to the contract with the embedded ReceiveMsg
+    Receive {
+        sender: HumanAddr,
+        from: HumanAddr,
+        amount: Uint128,
+        msg: Option<Binary>,
+    },
+    // lock the funds and get paired with the next player, queued at the same base bet,
+    // in a started two players game with the default settings
+    QueueForMatch {
+        nft_id: String,
+        base_bet: Coin,
+        // sha256 hash of the 32 secret bytes, revealed before the first roll
+        secret_hash: Binary,
+        permit: Permit,
+        // max xp and rating differences of the opponent, any opponent is accepted by default
+        window: Option<MatchWindow>,
+    },
+    // leave the matchmaking queue at the given base bet, refunding the locked funds
+    LeaveQueue {
+        base_bet: Coin,
+    },
+    // create the single-elimination tournament on behalf of the DAO member
+    CreateTournament {
+        // num of players, a power of two from 4 to 64
+        bracket_size: u8,
+        entry_fee: Coin,
+        // min xp of the dice NFT, player registers with
+        min_xp: Option<u32>,
+        // prize shares in basis points: champion, runner-up, each semifinal loser and so on
+        prize_split: Vec<u16>,
+    },
+    // register for the tournament, paying the entry fee; tournament starts, once the bracket is full
+    RegisterForTournament {
+        tournament_id: TournamentId,
+        nft_id: String,
+        // sha256 hash of the 32 secret bytes, revealed before the first roll of each match
+        secret_hash: Binary,
+        permit: Permit,
+    },
+    // withdraw the registration before the tournament started, refunding the entry fee
+    UnregisterFromTournament {
+        tournament_id: TournamentId,
+    },
+    // cancel the tournament before it started, refunding the entry fees
+    CancelTournament {
+        tournament_id: TournamentId,
+    },
+    // hide (or show again) the player and their NFTs in the leaderboards
+    SetLeaderboardOptOut {
+        opt_out: bool,
+    },
+    // submit a proposal on behalf of the DAO member
+    SubmitProposal {
+        description: String,
+        kind: ProposalKind,
+    },
+    // vote for the proposal with the weight of the given dice NFT xp
+    CastVote {
+        proposal_id: ProposalId,
+        nft_id: String,
+        permit: Permit,
+        vote: VoteOption,
+    },
+    // execute passed proposal or reject it, once voting period ended
+    ExecuteProposal {
+        proposal_id: ProposalId,
+    },
     JoinDao {
         nft: Option<JoinNftDetails>,
     },
@@ -51,6 +206,72 @@ pub enum HandleMsg {
     },
 }
 
+/// Message embedded into the SNIP-20 Send, locking the sent tokens in the game
+/// Token contract address stands for the base bet denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    CreateNewGameRoom {
+        nft_id: String,
+        base_bet: Uint128,
+        secret_hash: Binary,
+        permit: Permit,
+        shielded: Option<bool>,
+        max_players: Option<u8>,
+        num_of_dices: Option<u8>,
+        reroll_rounds: Option<u8>,
+        invitees: Option<Vec<HumanAddr>>,
+        invite_code_hash: Option<Binary>,
+        scoring_mode: Option<ScoringMode>,
+        best_of: Option<u8>,
+    },
+    JoinGame {
+        nft_id: String,
+        game_id: GameId,
+        secret_hash: Binary,
+        permit: Permit,
+        invite_code: Option<Binary>,
+    },
+}
+
+/// SNIP-20 token contract, accepted as the bet asset
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Snip20Token {
+    pub address: HumanAddr,
+    pub code_hash: String,
+}
+
+impl Snip20Token {
+    /// Denom of the bets, placed in the token
+    pub fn denom(&self) -> &str {
+        self.address.as_str()
+    }
+
+    /// Message, transferring given amount of tokens from the contract to the recipient
+    pub fn transfer_msg(&self, recipient: HumanAddr, amount: Uint128) -> StdResult<CosmosMsg> {
+        transfer_msg(
+            recipient,
+            amount,
+            None,
+            BLOCK_SIZE,
+            self.code_hash.clone(),
+            self.address.clone(),
+        )
+    }
+
+    /// Message, registering the contract to receive the Receive callbacks from the token
+    pub fn register_receive_msg(&self, contract_code_hash: String) -> StdResult<CosmosMsg> {
+        register_receive_msg(
+            contract_code_hash,
+            None,
+            BLOCK_SIZE,
+            self.code_hash.clone(),
+            self.address.clone(),
+        )
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct JoinNftDetails {
@@ -58,19 +279,181 @@ pub struct JoinNftDetails {
     pub permit: Permit,
 }
 
+/// Account, proving it's identity with the permit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Viewer {
+    pub address: HumanAddr,
+    pub permit: Permit,
+}
+
+/// Filters of the open game rooms, each one is skipped if not set
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct RoomFilter {
+    pub denom: Option<String>,
+    pub min_base_bet: Option<Uint128>,
+    pub max_base_bet: Option<Uint128>,
+    pub shielded: Option<bool>,
+    // index of the host player NFT xp tier
+    pub host_xp_tier: Option<u8>,
+}
+
+impl RoomFilter {
+    /// Check whether the game room summary passes all the filters
+    pub fn matches(&self, room: &RoomSummary) -> bool {
+        self.denom
+            .as_ref()
+            .map(|denom| room.base_bet.denom == *denom)
+            .unwrap_or(true)
+            && self
+                .min_base_bet
+                .map(|min_base_bet| room.base_bet.amount >= min_base_bet)
+                .unwrap_or(true)
+            && self
+                .max_base_bet
+                .map(|max_base_bet| room.base_bet.amount <= max_base_bet)
+                .unwrap_or(true)
+            && self
+                .shielded
+                .map(|shielded| room.shielded == shielded)
+                .unwrap_or(true)
+            && self
+                .host_xp_tier
+                .map(|host_xp_tier| room.host_xp_tier == host_xp_tier)
+                .unwrap_or(true)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    // retrieve all games by status provided
-    GamesByStatus { status: GameStatus },
+    // retrieve games by status provided, starting after the given id
+    GamesByStatus {
+        status: GameStatus,
+        start_after: Option<GameId>,
+        limit: Option<u32>,
+    },
+    // retrieve active games, player is seated at, starting after the given id
+    GamesByPlayer {
+        player: HumanAddr,
+        start_after: Option<GameId>,
+        limit: Option<u32>,
+    },
+    // retrieve summaries of the pending game rooms, passing the filter, sorted by creation height
+    // private rooms are only listed to the invited viewer
+    OpenRooms {
+        filter: Option<RoomFilter>,
+        viewer: Option<Viewer>,
+        start_after: Option<GameId>,
+        limit: Option<u32>,
+    },
     // get game under specified id
-    Game { game_id: GameId },
+    Game {
+        game_id: GameId,
+    },
+    // get game under specified id, revealing dices of the player in a shielded game
+    PlayerGame {
+        game_id: GameId,
+        player: HumanAddr,
+        permit: Permit,
+    },
+    // get result of the completed game under specified id
+    GameResult {
+        game_id: GameId,
+    },
+    // retrieve results of the games, player completed, starting from the latest one before the given id
+    PlayerGameHistory {
+        player: HumanAddr,
+        permit: Permit,
+        start_after: Option<GameId>,
+        limit: Option<u32>,
+    },
+    // get tournament with it's bracket under specified id
+    Tournament {
+        tournament_id: TournamentId,
+    },
+    // retrieve tournaments, starting after the given id
+    Tournaments {
+        start_after: Option<TournamentId>,
+        limit: Option<u32>,
+    },
+    // number of blocks, player has to make a move in
+    TurnTimeout {},
+    // number of blocks, pending game expires after
+    PendingGameExpiration {},
+    // points, each hand category scores in the newly created games
+    ScoringTable {},
+    // NFT privileges depending on it's xp
+    XpTiers {},
+    // rake in basis points, taken from the stake of the newly created games
+    Rake {},
+    // DAO treasury balance per each denom
+    Treasury {},
+    // funds held on behalf of the players and the treasury against the contract balance per each denom
+    Solvency {},
+    // whether NFT xp gain scales with the rating difference
+    XpScaling {},
+    // denoms, bets can be placed in, with their base bet limits
+    AcceptedDenoms {},
+    // SNIP-20 tokens, accepted as the bet asset
+    TokenWhitelist {},
+    // rating of the player address and it's history
+    PlayerRating {
+        player: HumanAddr,
+    },
+    // rating of the NFT and it's history
+    NftRating {
+        nft_id: String,
+    },
+    // retrieve leaderboard entries with their ranks, starting after the given rank
+    Leaderboard {
+        kind: LeaderboardKind,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    // voting period, quorum and threshold of the newly submitted proposals
+    GovernanceConfig {},
+    // get proposal under specified id with it's tally
+    Proposal {
+        proposal_id: ProposalId,
+    },
+    // retrieve proposals, starting after the given id
+    Proposals {
+        start_after: Option<ProposalId>,
+        limit: Option<u32>,
+    },
     // NFT address
     NftAddress {},
     // retrieve Nfts from player
-    PlayerNfts { player: HumanAddr, permit: Permit },
+    PlayerNfts {
+        player: HumanAddr,
+        permit: Permit,
+    },
     // retrieve nft info by it's token_id
-    NftInfo { token_id: String },
+    NftInfo {
+        token_id: String,
+    },
+}
+
+/// Funds, the contract holds in the denom, and it's balance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct DenomSolvency {
+    pub denom: String,
+    // held on behalf of the players: game pools, queue deposits and tournament entry fees
+    pub escrow: Uint128,
+    pub treasury: Uint128,
+    // not set for the SNIP-20 tokens, which balance can't be queried without a viewing key
+    pub balance: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SolvencyResponse {
+    // whether the balance covers the escrow and the treasury in each of the native denoms
+    pub solvent: bool,
+    pub denoms: Vec<DenomSolvency>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema)]
@@ -304,17 +687,176 @@ pub struct Extension {
     pub protected_attributes: Option<Vec<String>>,
 }
 
+/// Privileges of the NFTs, which xp is at least the given one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct XpTier {
+    pub min_xp: u32,
+    // min base bet of the game, NFT can be used in
+    pub min_base_bet: Option<Uint128>,
+    // max base bet of the game, NFT can be used in
+    pub max_base_bet: Uint128,
+    // whether NFT can be used in a shielded game
+    pub shielded: bool,
+}
+
+impl XpTier {
+    pub fn new(min_xp: u32, max_base_bet: u128, shielded: bool) -> Self {
+        Self {
+            min_xp,
+            min_base_bet: None,
+            max_base_bet: Uint128(max_base_bet),
+            shielded,
+        }
+    }
+}
+
+/// Default xp table
+pub fn default_xp_tiers() -> Vec<XpTier> {
+    vec![
+        XpTier::new(0, 1_000_000, false),
+        XpTier::new(10, 2_000_000, false),
+        XpTier::new(20, 4_000_000, true),
+        XpTier::new(40, 8_000_000, true),
+    ]
+}
+
+/// Denom, bets can be placed in, with it's own base bet limits
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AcceptedDenom {
+    // native (IBC ones included) denom or the whitelisted SNIP-20 token address
+    pub denom: String,
+    pub min_base_bet: Option<Uint128>,
+    pub max_base_bet: Option<Uint128>,
+    // xp tiers base bets are multiplied by, in basis points, to get their limits in the denom
+    pub xp_tier_multiplier: u32,
+}
+
+impl AcceptedDenom {
+    pub fn new(denom: &str) -> Self {
+        Self {
+            denom: denom.to_string(),
+            min_base_bet: None,
+            max_base_bet: None,
+            xp_tier_multiplier: BASIS_POINTS as u32,
+        }
+    }
+
+    /// Convert the base bet into the amount, xp tiers base bets are set in
+    pub fn xp_tier_base_bet(&self, base_bet: &Coin) -> ContractResult<Coin> {
+        let amount = base_bet
+            .amount
+            .u128()
+            .checked_mul(BASIS_POINTS as u128)
+            .ok_or_else(|| {
+                StdError::generic_err(ContractError::BaseBetOutOfRange {}.to_string())
+            })?;
+
+        Ok(Coin {
+            denom: base_bet.denom.clone(),
+            amount: Uint128(amount / self.xp_tier_multiplier as u128),
+        })
+    }
+
+    /// Ensure base bet fits into the denom limits
+    pub fn ensure_accepts(&self, base_bet: &Coin) -> ContractResult<()> {
+        let accepted = self
+            .min_base_bet
+            .map(|min_base_bet| base_bet.amount >= min_base_bet)
+            .unwrap_or(true)
+            && self
+                .max_base_bet
+                .map(|max_base_bet| base_bet.amount <= max_base_bet)
+                .unwrap_or(true);
+
+        if !accepted {
+            Err(StdError::generic_err(
+                ContractError::BaseBetOutOfRange {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Default accepted denoms
+pub fn default_accepted_denoms() -> Vec<AcceptedDenom> {
+    vec![AcceptedDenom::new("uscrt")]
+}
+
+/// Ensure accepted denoms are unique, have valid base bet bounds and xp tier multipliers
+pub fn ensure_correct_accepted_denoms(accepted_denoms: &[AcceptedDenom]) -> ContractResult<()> {
+    let unique_denoms = accepted_denoms
+        .iter()
+        .enumerate()
+        .all(|(i, accepted_denom)| {
+            accepted_denoms[..i]
+                .iter()
+                .all(|other| other.denom != accepted_denom.denom)
+        });
+
+    let correct_denoms = accepted_denoms.iter().all(|accepted_denom| {
+        let correct_base_bets = match (accepted_denom.min_base_bet, accepted_denom.max_base_bet) {
+            (Some(min_base_bet), Some(max_base_bet)) => min_base_bet <= max_base_bet,
+            _ => true,
+        };
+        correct_base_bets && accepted_denom.xp_tier_multiplier > 0
+    });
+
+    if unique_denoms && correct_denoms {
+        Ok(())
+    } else {
+        Err(StdError::generic_err(
+            ContractError::InvalidAcceptedDenoms {}.to_string(),
+        ))
+    }
+}
+
+/// Ensure xp tiers start from 0 xp, sorted by xp and have valid base bet bounds
+pub fn ensure_correct_xp_tiers(xp_tiers: &[XpTier]) -> ContractResult<()> {
+    let starts_from_zero_xp = xp_tiers.first().map(|tier| tier.min_xp) == Some(0);
+
+    let sorted_by_xp = xp_tiers
+        .windows(2)
+        .all(|tiers| tiers[0].min_xp < tiers[1].min_xp);
+
+    let correct_base_bets = xp_tiers.iter().all(|tier| {
+        tier.min_base_bet
+            .map(|min_base_bet| min_base_bet <= tier.max_base_bet)
+            .unwrap_or(true)
+    });
+
+    if starts_from_zero_xp && sorted_by_xp && correct_base_bets {
+        Ok(())
+    } else {
+        Err(StdError::generic_err(
+            ContractError::InvalidXpTiers {}.to_string(),
+        ))
+    }
+}
+
 impl Extension {
+    /// Get the xp tier NFT belongs to
+    pub fn xp_tier<'a>(&self, xp_tiers: &'a [XpTier]) -> Option<&'a XpTier> {
+        xp_tiers.iter().rev().find(|tier| tier.min_xp <= self.xp)
+    }
+
     /// Ensure NFT has enough XP for a game with a given base bet
-    pub fn ensure_enough_xp_for_the_base_bet(&self, base_bet: &Coin) -> ContractResult<()> {
-        let base_bet_amount = base_bet.amount.u128();
-
-        let enough_xp = match self.xp {
-            0..=10 => base_bet_amount == 1_000_000,
-            11..=20 => base_bet_amount <= 2_000_000,
-            21..=40 => base_bet_amount <= 4_000_000,
-            _ => base_bet_amount <= 8_000_000,
-        };
+    pub fn ensure_enough_xp_for_the_base_bet(
+        &self,
+        xp_tiers: &[XpTier],
+        base_bet: &Coin,
+    ) -> ContractResult<()> {
+        let enough_xp = self
+            .xp_tier(xp_tiers)
+            .map(|tier| {
+                tier.min_base_bet
+                    .map(|min_base_bet| base_bet.amount >= min_base_bet)
+                    .unwrap_or(true)
+                    && base_bet.amount <= tier.max_base_bet
+            })
+            .unwrap_or(false);
 
         if !enough_xp {
             Err(StdError::generic_err(
@@ -324,6 +866,21 @@ impl Extension {
             Ok(())
         }
     }
+
+    /// Ensure NFT has enough XP to access a shielded game
+    pub fn ensure_enough_xp_for_shielded_game(&self, xp_tiers: &[XpTier]) -> ContractResult<()> {
+        if !self
+            .xp_tier(xp_tiers)
+            .map(|tier| tier.shielded)
+            .unwrap_or(false)
+        {
+            Err(StdError::generic_err(
+                ContractError::NotEnoughXpForShieldedGame {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
 }
 
 /// attribute trait
@@ -377,3 +934,96 @@ pub struct Cw721Approval {
     pub spender: HumanAddr,
     pub expires: Expiration,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coin;
+
+    fn accepted_denom(
+        denom: &str,
+        min_base_bet: Option<u128>,
+        max_base_bet: Option<u128>,
+        xp_tier_multiplier: u32,
+    ) -> AcceptedDenom {
+        AcceptedDenom {
+            denom: denom.to_string(),
+            min_base_bet: min_base_bet.map(Uint128),
+            max_base_bet: max_base_bet.map(Uint128),
+            xp_tier_multiplier,
+        }
+    }
+
+    #[test]
+    fn accepted_denoms_are_unique_with_valid_limits() {
+        let invalid = Err(StdError::generic_err(
+            ContractError::InvalidAcceptedDenoms {}.to_string(),
+        ));
+
+        assert!(ensure_correct_accepted_denoms(&default_accepted_denoms()).is_ok());
+        assert!(ensure_correct_accepted_denoms(&[
+            accepted_denom("uscrt", Some(10), Some(10), 10_000),
+            accepted_denom("uatom", None, Some(5), 2_500),
+        ])
+        .is_ok());
+
+        assert_eq!(
+            ensure_correct_accepted_denoms(&[
+                AcceptedDenom::new("uscrt"),
+                AcceptedDenom::new("uscrt"),
+            ]),
+            invalid
+        );
+        assert_eq!(
+            ensure_correct_accepted_denoms(&[accepted_denom("uscrt", Some(11), Some(10), 10_000)]),
+            invalid
+        );
+        assert_eq!(
+            ensure_correct_accepted_denoms(&[accepted_denom("uscrt", None, None, 0)]),
+            invalid
+        );
+    }
+
+    #[test]
+    fn base_bet_should_fit_into_the_denom_limits() {
+        let out_of_range = Err(StdError::generic_err(
+            ContractError::BaseBetOutOfRange {}.to_string(),
+        ));
+        let accepted_denom = accepted_denom("uscrt", Some(10), Some(100), 10_000);
+
+        assert!(accepted_denom.ensure_accepts(&coin(10, "uscrt")).is_ok());
+        assert!(accepted_denom.ensure_accepts(&coin(100, "uscrt")).is_ok());
+        assert_eq!(
+            accepted_denom.ensure_accepts(&coin(9, "uscrt")),
+            out_of_range
+        );
+        assert_eq!(
+            accepted_denom.ensure_accepts(&coin(101, "uscrt")),
+            out_of_range
+        );
+        assert!(AcceptedDenom::new("uscrt")
+            .ensure_accepts(&coin(u128::MAX, "uscrt"))
+            .is_ok());
+    }
+
+    #[test]
+    fn base_bet_is_converted_with_the_xp_tier_multiplier() {
+        // 1 uatom is worth 4 uscrt, xp tiers base bets are set in
+        let accepted_denom = accepted_denom("uatom", None, None, 2_500);
+
+        assert_eq!(
+            accepted_denom.xp_tier_base_bet(&coin(25, "uatom")),
+            Ok(coin(100, "uatom"))
+        );
+        assert_eq!(
+            AcceptedDenom::new("uscrt").xp_tier_base_bet(&coin(25, "uscrt")),
+            Ok(coin(25, "uscrt"))
+        );
+        assert_eq!(
+            accepted_denom.xp_tier_base_bet(&coin(u128::MAX, "uatom")),
+            Err(StdError::generic_err(
+                ContractError::BaseBetOutOfRange {}.to_string()
+            ))
+        );
+    }
+}
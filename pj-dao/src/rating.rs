@@ -0,0 +1,154 @@
+use crate::game::Player;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Rating of the players and NFTs, that did not play yet
+pub const DEFAULT_RATING: u32 = 1200;
+
+// Max rating change per game
+pub const RATING_K_FACTOR: i64 = 32;
+
+// Num of the last rating changes kept in the history
+pub const MAX_RATING_HISTORY: usize = 20;
+
+// Xp, winner NFT gains for beating an equally rated opponent
+pub const BASE_XP_GAIN: u32 = 5;
+
+// Step of the rating difference in the expected scores table
+const EXPECTED_SCORES_STEP: i64 = 50;
+
+// Expected score (in basis points) of the stronger player by the rating difference, 0..=800 by 50
+// 1 / (1 + 10 ^ (-difference / 400)), precomputed to avoid floating point operations
+const EXPECTED_SCORES: [i64; 17] = [
+    5000, 5715, 6401, 7034, 7597, 8083, 8490, 8823, 9091, 9302, 9468, 9595, 9693, 9768, 9825, 9868,
+    9901,
+];
+
+// Score (in basis points) for a win, a draw and a loss
+const WIN_SCORE: i64 = 10_000;
+const DRAW_SCORE: i64 = 5_000;
+
+/// Get expected score (in basis points) of the player with the given rating against the opponent
+pub fn expected_score(rating: u32, opponent_rating: u32) -> i64 {
+    let difference = rating as i64 - opponent_rating as i64;
+
+    let max_difference = EXPECTED_SCORES_STEP * (EXPECTED_SCORES.len() as i64 - 1);
+    let abs_difference = difference.abs().min(max_difference);
+
+    // interpolate between the closest entries of the table
+    let i = (abs_difference / EXPECTED_SCORES_STEP) as usize;
+    let j = (i + 1).min(EXPECTED_SCORES.len() - 1);
+    let score = EXPECTED_SCORES[i]
+        + (EXPECTED_SCORES[j] - EXPECTED_SCORES[i]) * (abs_difference % EXPECTED_SCORES_STEP)
+            / EXPECTED_SCORES_STEP;
+
+    if difference >= 0 {
+        score
+    } else {
+        WIN_SCORE - score
+    }
+}
+
+/// Get xp, winner NFT gains for beating the opponents with the given average rating
+/// If scaled, beating stronger opponents counts for more (up to the double base xp gain)
+pub fn xp_gain(rating: u32, opponents_rating: u32, scaled: bool) -> u32 {
+    if !scaled {
+        return BASE_XP_GAIN;
+    }
+
+    // rounded to the nearest xp, so the gain spans the whole range
+    let gain = (BASE_XP_GAIN as i64 * 2 * (WIN_SCORE - expected_score(rating, opponents_rating))
+        + WIN_SCORE / 2)
+        / WIN_SCORE;
+
+    (gain as u32).max(1)
+}
+
+/// Rating change in the particular game
+/// The game is not identified, as the ratings are public and the player history is not
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RatingChange {
+    // rating after the game
+    pub rating: u32,
+    pub change: i32,
+}
+
+/// ELO-style rating of the player address or NFT
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Rating {
+    pub rating: u32,
+    // num of the games played
+    pub games: u32,
+    // last rating changes, the latest one goes last
+    pub history: Vec<RatingChange>,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Self {
+            rating: DEFAULT_RATING,
+            games: 0,
+            history: vec![],
+        }
+    }
+}
+
+impl Rating {
+    /// Apply rating change, the game resulted in
+    pub fn apply(&mut self, change: i32) {
+        self.rating = (self.rating as i64 + change as i64).max(0) as u32;
+        self.games += 1;
+
+        self.history.push(RatingChange {
+            rating: self.rating,
+            change,
+        });
+
+        if self.history.len() > MAX_RATING_HISTORY {
+            self.history.remove(0);
+        }
+    }
+}
+
+/// Update ratings of all the game players, indexed by their seats
+/// Each winner beats each of the other players, the rest of pairs are draws
+pub fn apply_rating_changes(ratings: &mut [Rating], winners: &[Player]) {
+    let num_of_players = ratings.len() as i64;
+
+    let changes: Vec<i32> = (0..ratings.len())
+        .map(|player| {
+            let score_difference: i64 = (0..ratings.len())
+                .filter(|opponent| *opponent != player)
+                .map(|opponent| {
+                    let score = match (winners.contains(&player), winners.contains(&opponent)) {
+                        (true, false) => WIN_SCORE,
+                        (false, true) => 0,
+                        _ => DRAW_SCORE,
+                    };
+                    score - expected_score(ratings[player].rating, ratings[opponent].rating)
+                })
+                .sum();
+
+            (RATING_K_FACTOR * score_difference / ((num_of_players - 1) * WIN_SCORE)) as i32
+        })
+        .collect();
+
+    for (rating, change) in ratings.iter_mut().zip(changes) {
+        rating.apply(change);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_xp_gain_ranges_from_1_to_the_double_base_gain() {
+        assert_eq!(xp_gain(1200, 1200, true), BASE_XP_GAIN);
+        assert_eq!(xp_gain(400, 2000, true), BASE_XP_GAIN * 2);
+        assert_eq!(xp_gain(2000, 400, true), 1);
+        assert_eq!(xp_gain(400, 2000, false), BASE_XP_GAIN);
+    }
+}
@@ -1,18 +1,32 @@
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::any::type_name;
 
 use crate::contract::GameId;
-use crate::game::GameDetails;
+use crate::game::{GameDetails, GameResult, GameStatus, ScoringTable};
+use crate::governance::{GovernanceConfig, Proposal, ProposalId};
+use crate::leaderboard::{Leaderboard, LeaderboardKind, PlayerStats};
+use crate::matchmaking::MatchQueue;
+use crate::msg::{AcceptedDenom, Snip20Token, XpTier};
+use crate::rating::Rating;
+use crate::tournament::{TournamentDetails, TournamentId};
 
-use cosmwasm_std::{CanonicalAddr, HumanAddr, ReadonlyStorage, StdError, StdResult, Storage};
+use cosmwasm_std::{CanonicalAddr, Coin, HumanAddr, ReadonlyStorage, StdError, StdResult, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
 
 use secret_toolkit::serialization::{Bincode2, Json, Serde};
+use secret_toolkit::storage::{AppendStore, AppendStoreMut};
 
 pub static CONFIG_KEY: &[u8] = b"config";
 
 /// prefix for the games
 pub const PREFIX_GAMES: &[u8] = b"games";
 
+/// prefix for the ids of the games by their status, linked list keyed by the game ids
+pub const PREFIX_STATUS_GAMES: &[u8] = b"statusGames";
+
+/// prefix for the ids of the active games by their players, linked list keyed by the game ids
+pub const PREFIX_PLAYER_ACTIVE_GAMES: &[u8] = b"playerActiveGames";
+
 /// prefix for the last created game id
 pub const PREFIX_LAST_GAME_INDEX: &[u8] = b"gameId";
 
@@ -28,6 +42,99 @@ pub const PREFIX_NFT_CODE_HASH: &[u8] = b"nftCodeHash";
 /// prefix for the games
 pub const PREFIX_ADMIN: &[u8] = b"admin";
 
+/// prefix for the number of blocks, player has to make a move in
+pub const PREFIX_TURN_TIMEOUT: &[u8] = b"turnTimeout";
+
+/// prefix for the number of blocks, pending game expires after
+pub const PREFIX_PENDING_GAME_EXPIRATION: &[u8] = b"pendingGameExpiration";
+
+/// prefix for the active scoring table
+pub const PREFIX_SCORING_TABLE: &[u8] = b"scoringTable";
+
+/// prefix for the xp tiers table
+pub const PREFIX_XP_TIERS: &[u8] = b"xpTiers";
+
+/// prefix for the results of the completed games
+pub const PREFIX_GAME_RESULTS: &[u8] = b"gameResults";
+
+/// prefix for the ids of the games, each player completed, in the order of completion
+pub const PREFIX_PLAYER_GAMES: &[u8] = b"playerGames";
+
+/// prefix for the positions of the completed games in the player history
+pub const PREFIX_PLAYER_GAME_POSITIONS: &[u8] = b"playerGamePositions";
+
+/// prefix for the rake in basis points
+pub const PREFIX_RAKE: &[u8] = b"rake";
+
+/// prefix for the DAO treasury
+pub const PREFIX_TREASURY: &[u8] = b"treasury";
+
+/// prefix for the SNIP-20 tokens, the DAO treasury holds balances in
+pub const PREFIX_TREASURY_TOKENS: &[u8] = b"treasuryTokens";
+
+/// prefix for the funds, held on behalf of the players, per each denom
+pub const PREFIX_ESCROW: &[u8] = b"escrow";
+
+/// prefix for the SNIP-20 tokens, the escrow holds funds in
+pub const PREFIX_ESCROW_TOKENS: &[u8] = b"escrowTokens";
+
+/// prefix for the entropy pool, mixed with the players secrets and never exposed to them
+pub const PREFIX_ENTROPY: &[u8] = b"entropy";
+
+/// prefix for the contract address
+pub const PREFIX_CONTRACT_ADDRESS: &[u8] = b"contractAddress";
+
+/// prefix for whether NFT xp gain scales with the rating difference
+pub const PREFIX_XP_SCALING: &[u8] = b"xpScaling";
+
+/// prefix for the player address ratings
+pub const PREFIX_PLAYER_RATINGS: &[u8] = b"playerRatings";
+
+/// prefix for the NFT ratings
+pub const PREFIX_NFT_RATINGS: &[u8] = b"nftRatings";
+
+/// prefix for the matchmaking queues by the base bet
+pub const PREFIX_MATCH_QUEUES: &[u8] = b"matchQueues";
+
+/// prefix for the tournaments
+pub const PREFIX_TOURNAMENTS: &[u8] = b"tournaments";
+
+/// prefix for the last created tournament id
+pub const PREFIX_LAST_TOURNAMENT_INDEX: &[u8] = b"tournamentId";
+
+/// prefix for the denoms, bets can be placed in
+pub const PREFIX_ACCEPTED_DENOMS: &[u8] = b"acceptedDenoms";
+
+/// prefix for the SNIP-20 tokens, accepted as the bet asset
+pub const PREFIX_TOKEN_WHITELIST: &[u8] = b"tokenWhitelist";
+
+/// prefix for the leaderboards
+pub const PREFIX_LEADERBOARDS: &[u8] = b"leaderboards";
+
+/// prefix for the denoms, net winnings leaderboards are kept for
+pub const PREFIX_LEADERBOARD_DENOMS: &[u8] = b"leaderboardDenoms";
+
+/// prefix for the player stats
+pub const PREFIX_PLAYER_STATS: &[u8] = b"playerStats";
+
+/// prefix for the players, who opted out of the leaderboards
+pub const PREFIX_LEADERBOARD_OPT_OUTS: &[u8] = b"leaderboardOptOuts";
+
+/// prefix for the governance config
+pub const PREFIX_GOVERNANCE_CONFIG: &[u8] = b"governanceConfig";
+
+/// prefix for the proposals
+pub const PREFIX_PROPOSALS: &[u8] = b"proposals";
+
+/// prefix for the last submitted proposal id
+pub const PREFIX_LAST_PROPOSAL_INDEX: &[u8] = b"proposalId";
+
+/// prefix for the NFTs, that voted for the proposals
+pub const PREFIX_VOTES: &[u8] = b"votes";
+
+/// prefix for the revoked permits, permits are revoked with the nft contract
+pub const PREFIX_REVOKED_PERMITS: &str = "revokedPermits";
+
 // last game index
 pub fn save_last_game_index<S: Storage>(storage: &mut S, index: &GameId) -> StdResult<()> {
     save(storage, PREFIX_LAST_GAME_INDEX, index)
@@ -47,6 +154,392 @@ pub fn save_admin<S: Storage>(storage: &mut S, admin: &HumanAddr) -> StdResult<(
 pub fn load_admin<S: Storage>(storage: &S) -> StdResult<HumanAddr> {
     load(storage, PREFIX_ADMIN)
 }
+// turn timeout
+pub fn save_turn_timeout<S: Storage>(storage: &mut S, blocks: u64) -> StdResult<()> {
+    save(storage, PREFIX_TURN_TIMEOUT, &blocks)
+}
+
+pub fn load_turn_timeout<S: Storage>(storage: &S) -> StdResult<u64> {
+    load(storage, PREFIX_TURN_TIMEOUT)
+}
+
+// pending game expiration
+pub fn save_pending_game_expiration<S: Storage>(
+    storage: &mut S,
+    blocks: Option<u64>,
+) -> StdResult<()> {
+    save(storage, PREFIX_PENDING_GAME_EXPIRATION, &blocks)
+}
+
+pub fn load_pending_game_expiration<S: Storage>(storage: &S) -> StdResult<Option<u64>> {
+    load(storage, PREFIX_PENDING_GAME_EXPIRATION)
+}
+
+// scoring table
+pub fn save_scoring_table<S: Storage>(
+    storage: &mut S,
+    scoring_table: &ScoringTable,
+) -> StdResult<()> {
+    json_save(storage, PREFIX_SCORING_TABLE, scoring_table)
+}
+
+pub fn load_scoring_table<S: Storage>(storage: &S) -> StdResult<ScoringTable> {
+    json_load(storage, PREFIX_SCORING_TABLE)
+}
+
+// xp tiers
+pub fn save_xp_tiers<S: Storage>(storage: &mut S, xp_tiers: &[XpTier]) -> StdResult<()> {
+    json_save(storage, PREFIX_XP_TIERS, &xp_tiers)
+}
+
+pub fn load_xp_tiers<S: Storage>(storage: &S) -> StdResult<Vec<XpTier>> {
+    json_load(storage, PREFIX_XP_TIERS)
+}
+
+// rake
+pub fn save_rake<S: Storage>(storage: &mut S, rake: u16) -> StdResult<()> {
+    save(storage, PREFIX_RAKE, &rake)
+}
+
+pub fn load_rake<S: Storage>(storage: &S) -> StdResult<u16> {
+    load(storage, PREFIX_RAKE)
+}
+
+// treasury, a balance per each denom
+pub fn save_treasury<S: Storage>(storage: &mut S, treasury: &[Coin]) -> StdResult<()> {
+    json_save(storage, PREFIX_TREASURY, &treasury)
+}
+
+pub fn load_treasury<S: Storage>(storage: &S) -> StdResult<Vec<Coin>> {
+    json_load(storage, PREFIX_TREASURY)
+}
+
+// SNIP-20 tokens of the treasury balances, kept even if they are removed from the whitelist later
+pub fn save_treasury_tokens<S: Storage>(storage: &mut S, tokens: &[Snip20Token]) -> StdResult<()> {
+    json_save(storage, PREFIX_TREASURY_TOKENS, &tokens)
+}
+
+pub fn load_treasury_tokens<S: Storage>(storage: &S) -> StdResult<Vec<Snip20Token>> {
+    json_may_load(storage, PREFIX_TREASURY_TOKENS).map(Option::unwrap_or_default)
+}
+
+// escrow, an amount held on behalf of the players per each denom
+pub fn save_escrow<S: Storage>(storage: &mut S, escrow: &[Coin]) -> StdResult<()> {
+    json_save(storage, PREFIX_ESCROW, &escrow)
+}
+
+pub fn load_escrow<S: Storage>(storage: &S) -> StdResult<Vec<Coin>> {
+    json_load(storage, PREFIX_ESCROW)
+}
+
+// SNIP-20 tokens of the escrow balances, kept even if they are removed from the whitelist later
+pub fn save_escrow_tokens<S: Storage>(storage: &mut S, tokens: &[Snip20Token]) -> StdResult<()> {
+    json_save(storage, PREFIX_ESCROW_TOKENS, &tokens)
+}
+
+pub fn load_escrow_tokens<S: Storage>(storage: &S) -> StdResult<Vec<Snip20Token>> {
+    json_may_load(storage, PREFIX_ESCROW_TOKENS).map(Option::unwrap_or_default)
+}
+
+// entropy pool, empty until the first secret is mixed in
+pub fn save_entropy<S: Storage>(storage: &mut S, entropy: &[u8; 32]) -> StdResult<()> {
+    save(storage, PREFIX_ENTROPY, entropy)
+}
+
+pub fn load_entropy<S: Storage>(storage: &S) -> StdResult<[u8; 32]> {
+    may_load(storage, PREFIX_ENTROPY).map(Option::unwrap_or_default)
+}
+
+// contract address, so the queries can read it's balance
+pub fn save_contract_address<S: Storage>(storage: &mut S, address: &HumanAddr) -> StdResult<()> {
+    save(storage, PREFIX_CONTRACT_ADDRESS, address)
+}
+
+pub fn load_contract_address<S: Storage>(storage: &S) -> StdResult<HumanAddr> {
+    load(storage, PREFIX_CONTRACT_ADDRESS)
+}
+
+// xp scaling
+pub fn save_xp_scaling<S: Storage>(storage: &mut S, enabled: bool) -> StdResult<()> {
+    save(storage, PREFIX_XP_SCALING, &enabled)
+}
+
+pub fn load_xp_scaling<S: Storage>(storage: &S) -> StdResult<bool> {
+    load(storage, PREFIX_XP_SCALING)
+}
+
+// accepted denoms with their base bet limits
+pub fn save_accepted_denoms<S: Storage>(
+    storage: &mut S,
+    accepted_denoms: &[AcceptedDenom],
+) -> StdResult<()> {
+    json_save(storage, PREFIX_ACCEPTED_DENOMS, &accepted_denoms)
+}
+
+pub fn load_accepted_denoms<S: Storage>(storage: &S) -> StdResult<Vec<AcceptedDenom>> {
+    json_load(storage, PREFIX_ACCEPTED_DENOMS)
+}
+
+// SNIP-20 tokens whitelist, only native coins are accepted until it's set
+pub fn save_token_whitelist<S: Storage>(storage: &mut S, tokens: &[Snip20Token]) -> StdResult<()> {
+    json_save(storage, PREFIX_TOKEN_WHITELIST, &tokens)
+}
+
+pub fn load_token_whitelist<S: Storage>(storage: &S) -> StdResult<Vec<Snip20Token>> {
+    json_may_load(storage, PREFIX_TOKEN_WHITELIST).map(Option::unwrap_or_default)
+}
+
+// Get player rating storage key from it's address
+pub fn get_player_rating_key(player: &CanonicalAddr) -> Vec<u8> {
+    PREFIX_PLAYER_RATINGS
+        .iter()
+        .chain(player.as_slice().iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_player_rating<S: Storage>(
+    storage: &mut S,
+    player: &CanonicalAddr,
+    rating: &Rating,
+) -> StdResult<()> {
+    json_save(storage, &get_player_rating_key(player), rating)
+}
+
+// players, that did not play yet, have the default rating
+pub fn load_player_rating<S: Storage>(storage: &S, player: &CanonicalAddr) -> StdResult<Rating> {
+    json_may_load(storage, &get_player_rating_key(player)).map(Option::unwrap_or_default)
+}
+
+// Get NFT rating storage key from it's id
+pub fn get_nft_rating_key(nft_id: &str) -> Vec<u8> {
+    PREFIX_NFT_RATINGS
+        .iter()
+        .chain(nft_id.as_bytes().iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_nft_rating<S: Storage>(
+    storage: &mut S,
+    nft_id: &str,
+    rating: &Rating,
+) -> StdResult<()> {
+    json_save(storage, &get_nft_rating_key(nft_id), rating)
+}
+
+// NFTs, that did not play yet, have the default rating
+pub fn load_nft_rating<S: Storage>(storage: &S, nft_id: &str) -> StdResult<Rating> {
+    json_may_load(storage, &get_nft_rating_key(nft_id)).map(Option::unwrap_or_default)
+}
+
+// Get leaderboard storage key from it's kind
+pub fn get_leaderboard_key(kind: &LeaderboardKind) -> Vec<u8> {
+    let kind_key = match kind {
+        LeaderboardKind::Wins => b"wins".to_vec(),
+        LeaderboardKind::NetWinnings { denom } => [b"winnings", denom.as_bytes()].concat(),
+        LeaderboardKind::Xp => b"xp".to_vec(),
+        LeaderboardKind::Rating => b"rating".to_vec(),
+    };
+
+    PREFIX_LEADERBOARDS
+        .iter()
+        .chain(kind_key.iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_leaderboard<S: Storage>(
+    storage: &mut S,
+    kind: &LeaderboardKind,
+    leaderboard: &Leaderboard,
+) -> StdResult<()> {
+    json_save(storage, &get_leaderboard_key(kind), leaderboard)
+}
+
+pub fn load_leaderboard<S: Storage>(storage: &S, kind: &LeaderboardKind) -> StdResult<Leaderboard> {
+    json_may_load(storage, &get_leaderboard_key(kind)).map(Option::unwrap_or_default)
+}
+
+pub fn save_leaderboard_denoms<S: Storage>(storage: &mut S, denoms: &[String]) -> StdResult<()> {
+    json_save(storage, PREFIX_LEADERBOARD_DENOMS, &denoms)
+}
+
+pub fn load_leaderboard_denoms<S: Storage>(storage: &S) -> StdResult<Vec<String>> {
+    json_may_load(storage, PREFIX_LEADERBOARD_DENOMS).map(Option::unwrap_or_default)
+}
+
+// Get player stats storage key from it's address
+pub fn get_player_stats_key(player: &CanonicalAddr) -> Vec<u8> {
+    PREFIX_PLAYER_STATS
+        .iter()
+        .chain(player.as_slice().iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_player_stats<S: Storage>(
+    storage: &mut S,
+    player: &CanonicalAddr,
+    stats: &PlayerStats,
+) -> StdResult<()> {
+    json_save(storage, &get_player_stats_key(player), stats)
+}
+
+pub fn load_player_stats<S: Storage>(
+    storage: &S,
+    player: &CanonicalAddr,
+) -> StdResult<PlayerStats> {
+    json_may_load(storage, &get_player_stats_key(player)).map(Option::unwrap_or_default)
+}
+
+// Get leaderboard opt out storage key from the player address
+pub fn get_leaderboard_opt_out_key(player: &CanonicalAddr) -> Vec<u8> {
+    PREFIX_LEADERBOARD_OPT_OUTS
+        .iter()
+        .chain(player.as_slice().iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_leaderboard_opt_out<S: Storage>(
+    storage: &mut S,
+    player: &CanonicalAddr,
+    opt_out: bool,
+) -> StdResult<()> {
+    save(storage, &get_leaderboard_opt_out_key(player), &opt_out)
+}
+
+// players are listed in the leaderboards, unless they opted out
+pub fn load_leaderboard_opt_out<S: Storage>(
+    storage: &S,
+    player: &CanonicalAddr,
+) -> StdResult<bool> {
+    may_load(storage, &get_leaderboard_opt_out_key(player)).map(Option::unwrap_or_default)
+}
+
+// Get matchmaking queue storage key from the base bet
+pub fn get_match_queue_key(base_bet: &Coin) -> Vec<u8> {
+    PREFIX_MATCH_QUEUES
+        .iter()
+        .chain(base_bet.amount.u128().to_be_bytes().iter())
+        .chain(base_bet.denom.as_bytes().iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_match_queue<S: Storage>(
+    storage: &mut S,
+    base_bet: &Coin,
+    queue: &MatchQueue,
+) -> StdResult<()> {
+    json_save(storage, &get_match_queue_key(base_bet), queue)
+}
+
+// queue is empty, until the first player queues at the base bet
+pub fn load_match_queue<S: Storage>(storage: &S, base_bet: &Coin) -> StdResult<MatchQueue> {
+    json_may_load(storage, &get_match_queue_key(base_bet)).map(Option::unwrap_or_default)
+}
+
+pub fn save_last_tournament_index<S: Storage>(
+    storage: &mut S,
+    index: &TournamentId,
+) -> StdResult<()> {
+    save(storage, PREFIX_LAST_TOURNAMENT_INDEX, index)
+}
+
+pub fn load_last_tournament_index<S: Storage>(storage: &S) -> StdResult<TournamentId> {
+    load(storage, PREFIX_LAST_TOURNAMENT_INDEX)
+}
+
+// Get tournament storage key from it's id
+pub fn get_tournament_key(tournament_id: TournamentId) -> Vec<u8> {
+    PREFIX_TOURNAMENTS
+        .iter()
+        .chain(tournament_id.to_be_bytes().iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_tournament<S: Storage>(
+    storage: &mut S,
+    tournament_id: TournamentId,
+    tournament: &TournamentDetails,
+) -> StdResult<()> {
+    json_save(storage, &get_tournament_key(tournament_id), tournament)
+}
+
+pub fn load_tournament<S: Storage>(
+    storage: &S,
+    tournament_id: TournamentId,
+) -> StdResult<TournamentDetails> {
+    json_load(storage, &get_tournament_key(tournament_id))
+}
+
+// governance config
+pub fn save_governance_config<S: Storage>(
+    storage: &mut S,
+    config: &GovernanceConfig,
+) -> StdResult<()> {
+    json_save(storage, PREFIX_GOVERNANCE_CONFIG, config)
+}
+
+pub fn load_governance_config<S: Storage>(storage: &S) -> StdResult<GovernanceConfig> {
+    json_load(storage, PREFIX_GOVERNANCE_CONFIG)
+}
+
+// last proposal index
+pub fn save_last_proposal_index<S: Storage>(storage: &mut S, index: &ProposalId) -> StdResult<()> {
+    save(storage, PREFIX_LAST_PROPOSAL_INDEX, index)
+}
+
+pub fn load_last_proposal_index<S: Storage>(storage: &S) -> StdResult<ProposalId> {
+    load(storage, PREFIX_LAST_PROPOSAL_INDEX)
+}
+
+// Get proposal storage key from it's id
+pub fn get_proposal_key(proposal_id: ProposalId) -> Vec<u8> {
+    PREFIX_PROPOSALS
+        .iter()
+        .chain(proposal_id.to_be_bytes().iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_proposal<S: Storage>(
+    storage: &mut S,
+    proposal_id: ProposalId,
+    value: &Proposal,
+) -> StdResult<()> {
+    json_save(storage, &get_proposal_key(proposal_id), value)
+}
+
+pub fn load_proposal<S: Storage>(storage: &S, proposal_id: ProposalId) -> StdResult<Proposal> {
+    json_load(storage, &get_proposal_key(proposal_id))
+}
+
+// Get storage key of the given NFT vote for the proposal
+pub fn get_vote_key(proposal_id: ProposalId, nft_id: &str) -> Vec<u8> {
+    PREFIX_VOTES
+        .iter()
+        .chain(proposal_id.to_be_bytes().iter())
+        .chain(nft_id.as_bytes().iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_vote<S: Storage>(
+    storage: &mut S,
+    proposal_id: ProposalId,
+    nft_id: &str,
+) -> StdResult<()> {
+    save(storage, &get_vote_key(proposal_id, nft_id), &())
+}
+
+pub fn load_vote<S: Storage>(storage: &S, proposal_id: ProposalId, nft_id: &str) -> StdResult<()> {
+    load(storage, &get_vote_key(proposal_id, nft_id))
+}
+
 // supporting nft contract
 // currently only 1
 pub fn save_nft_address<S: Storage>(storage: &mut S, nft_address: &HumanAddr) -> StdResult<()> {
@@ -96,6 +589,8 @@ pub fn save_game<S: Storage>(
     value: &GameDetails,
 ) -> StdResult<()> {
     let key: Vec<u8> = get_game_key(game_id);
+    let previous: Option<GameDetails> = json_may_load(storage, &key)?;
+    update_game_indices(storage, game_id, previous.as_ref(), Some(value))?;
     json_save(storage, &key, value)
 }
 
@@ -104,9 +599,278 @@ pub fn load_game<S: Storage>(storage: &S, game_id: GameId) -> StdResult<GameDeta
     json_load(storage, &key)
 }
 
-pub fn remove_game<S: Storage>(storage: &mut S, game_id: GameId) {
+pub fn remove_game<S: Storage>(storage: &mut S, game_id: GameId) -> StdResult<()> {
     let key: Vec<u8> = get_game_key(game_id);
-    remove(storage, &key)
+    let previous: Option<GameDetails> = json_may_load(storage, &key)?;
+    update_game_indices(storage, game_id, previous.as_ref(), None)?;
+    remove(storage, &key);
+    Ok(())
+}
+
+// Get storage key of the games ids from their status
+pub fn get_status_games_key(status: &GameStatus) -> Vec<u8> {
+    let status_byte: u8 = match status {
+        GameStatus::Pending => 0,
+        GameStatus::Started => 1,
+        GameStatus::ReRoll => 2,
+        GameStatus::Finished => 3,
+    };
+    PREFIX_STATUS_GAMES
+        .iter()
+        .chain(std::iter::once(&status_byte))
+        .copied()
+        .collect()
+}
+
+// Get storage key of the active games ids from the player address
+// address is prefixed with it's length, so the games of one player never follow the key of another one
+pub fn get_player_active_games_key(player: &HumanAddr) -> Vec<u8> {
+    PREFIX_PLAYER_ACTIVE_GAMES
+        .iter()
+        .chain((player.len() as u16).to_be_bytes().iter())
+        .chain(player.as_str().as_bytes().iter())
+        .copied()
+        .collect()
+}
+
+// ids of the games with the given status, following the given one, in the order they got the status
+pub fn load_status_games<'a, S: ReadonlyStorage>(
+    storage: &'a S,
+    status: &GameStatus,
+    start_after: Option<GameId>,
+) -> Box<dyn Iterator<Item = StdResult<GameId>> + 'a> {
+    index_iter(storage, get_status_games_key(status), start_after)
+}
+
+// ids of the active games, player is seated at, following the given one, in the order they joined them
+pub fn load_player_active_games<'a, S: ReadonlyStorage>(
+    storage: &'a S,
+    player: &HumanAddr,
+    start_after: Option<GameId>,
+) -> Box<dyn Iterator<Item = StdResult<GameId>> + 'a> {
+    index_iter(storage, get_player_active_games_key(player), start_after)
+}
+
+/// Keep the status and the player indices in sync with the saved (or removed, if `None`) game
+fn update_game_indices<S: Storage>(
+    storage: &mut S,
+    game_id: GameId,
+    previous: Option<&GameDetails>,
+    current: Option<&GameDetails>,
+) -> StdResult<()> {
+    let previous_status = previous.map(|game_details| &game_details.game.status);
+    let current_status = current.map(|game_details| &game_details.game.status);
+
+    if previous_status != current_status {
+        if let Some(status) = previous_status {
+            remove_from_index(storage, &get_status_games_key(status), game_id)?;
+        }
+        if let Some(status) = current_status {
+            insert_into_index(storage, &get_status_games_key(status), game_id)?;
+        }
+    }
+
+    let players = |game_details: Option<&GameDetails>| -> Vec<HumanAddr> {
+        game_details
+            .map(|game_details| {
+                game_details
+                    .game
+                    .seats
+                    .iter()
+                    .map(|seat| seat.address.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let previous_players = players(previous);
+    let current_players = players(current);
+
+    for player in previous_players.iter() {
+        if !current_players.contains(player) {
+            remove_from_index(storage, &get_player_active_games_key(player), game_id)?;
+        }
+    }
+    for player in current_players.iter() {
+        if !previous_players.contains(player) {
+            insert_into_index(storage, &get_player_active_games_key(player), game_id)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Neighbours of the game id in the index, each index is a doubly linked list keyed by the game ids,
+/// the links stored under the index key itself point to it's last (`prev`) and first (`next`) game ids
+#[derive(Serialize, Deserialize, Default)]
+struct IndexLinks {
+    prev: Option<GameId>,
+    next: Option<GameId>,
+}
+
+// Get storage key of the links of the game id in the index, or of the index ends if `None`
+fn get_index_links_key(index_key: &[u8], game_id: Option<GameId>) -> Vec<u8> {
+    match game_id {
+        Some(game_id) => index_key
+            .iter()
+            .chain(game_id.to_be_bytes().iter())
+            .copied()
+            .collect(),
+        None => index_key.to_vec(),
+    }
+}
+
+fn load_index_links<S: ReadonlyStorage>(
+    storage: &S,
+    index_key: &[u8],
+    game_id: Option<GameId>,
+) -> StdResult<Option<IndexLinks>> {
+    json_may_load(storage, &get_index_links_key(index_key, game_id))
+}
+
+fn update_index_links<S: Storage>(
+    storage: &mut S,
+    index_key: &[u8],
+    game_id: Option<GameId>,
+    update: impl FnOnce(&mut IndexLinks),
+) -> StdResult<()> {
+    let mut links = load_index_links(storage, index_key, game_id)?.unwrap_or_default();
+    update(&mut links);
+    json_save(storage, &get_index_links_key(index_key, game_id), &links)
+}
+
+/// Append game id to the index, stored under the given key
+fn insert_into_index<S: Storage>(storage: &mut S, key: &[u8], game_id: GameId) -> StdResult<()> {
+    let last = load_index_links(storage, key, None)?
+        .unwrap_or_default()
+        .prev;
+
+    update_index_links(storage, key, Some(game_id), |links| {
+        links.prev = last;
+        links.next = None;
+    })?;
+    update_index_links(storage, key, last, |links| links.next = Some(game_id))?;
+    update_index_links(storage, key, None, |links| links.prev = Some(game_id))
+}
+
+/// Remove game id from the index, stored under the given key, linking it's neighbours together
+fn remove_from_index<S: Storage>(storage: &mut S, key: &[u8], game_id: GameId) -> StdResult<()> {
+    let links = match load_index_links(storage, key, Some(game_id))? {
+        Some(links) => links,
+        None => return Ok(()),
+    };
+
+    update_index_links(storage, key, links.prev, |prev| prev.next = links.next)?;
+    update_index_links(storage, key, links.next, |next| next.prev = links.prev)?;
+    remove(storage, &get_index_links_key(key, Some(game_id)));
+    Ok(())
+}
+
+/// Iterate over the game ids of the index, stored under the given key, following the links
+/// starting after the given game id, so reading a page takes as many entries as it holds
+/// The page is empty, if the given game id is no longer in the index
+fn index_iter<'a, S: ReadonlyStorage>(
+    storage: &'a S,
+    index_key: Vec<u8>,
+    start_after: Option<GameId>,
+) -> Box<dyn Iterator<Item = StdResult<GameId>> + 'a> {
+    let mut next = load_index_links(storage, &index_key, start_after)
+        .map(|links| links.and_then(|links| links.next));
+
+    Box::new(std::iter::from_fn(move || {
+        let game_id = match std::mem::replace(&mut next, Ok(None)) {
+            Ok(game_id) => game_id?,
+            Err(err) => return Some(Err(err)),
+        };
+        next = load_index_links(storage, &index_key, Some(game_id))
+            .map(|links| links.and_then(|links| links.next));
+        Some(Ok(game_id))
+    }))
+}
+
+// Get game result storage key from the game id
+pub fn get_game_result_key(game_id: GameId) -> Vec<u8> {
+    PREFIX_GAME_RESULTS
+        .iter()
+        .chain(game_id.to_be_bytes().iter())
+        .copied()
+        .collect()
+}
+
+pub fn save_game_result<S: Storage>(
+    storage: &mut S,
+    game_id: GameId,
+    result: &GameResult,
+) -> StdResult<()> {
+    json_save(storage, &get_game_result_key(game_id), result)
+}
+
+pub fn load_game_result<S: Storage>(storage: &S, game_id: GameId) -> StdResult<GameResult> {
+    json_load(storage, &get_game_result_key(game_id))
+}
+
+// Get storage key of the completed game position in the player history
+pub fn get_player_game_position_key(player: &CanonicalAddr, game_id: GameId) -> Vec<u8> {
+    PREFIX_PLAYER_GAME_POSITIONS
+        .iter()
+        .chain((player.len() as u16).to_be_bytes().iter())
+        .chain(player.as_slice().iter())
+        .chain(game_id.to_be_bytes().iter())
+        .copied()
+        .collect()
+}
+
+// append completed game id to the player history
+pub fn save_player_game<S: Storage>(
+    storage: &mut S,
+    player: &CanonicalAddr,
+    game_id: GameId,
+) -> StdResult<()> {
+    let position = {
+        let mut history_storage =
+            PrefixedStorage::multilevel(&[PREFIX_PLAYER_GAMES, player.as_slice()], storage);
+        let mut history = AppendStoreMut::attach_or_create(&mut history_storage)?;
+        history.push(&game_id)?;
+        history.len() - 1
+    };
+    save(
+        storage,
+        &get_player_game_position_key(player, game_id),
+        &position,
+    )
+}
+
+// completed games ids of the player, preceding the given one, the latest one goes first
+pub fn load_player_games<S: ReadonlyStorage>(
+    storage: &S,
+    player: &CanonicalAddr,
+    start_after: Option<GameId>,
+    limit: u32,
+) -> StdResult<Vec<GameId>> {
+    let start_after_position: Option<u32> = match start_after {
+        Some(game_id) => {
+            match may_load(storage, &get_player_game_position_key(player, game_id))? {
+                Some(position) => Some(position),
+                // the game is not in the player history
+                None => return Ok(vec![]),
+            }
+        }
+        None => None,
+    };
+
+    let history_storage =
+        ReadonlyPrefixedStorage::multilevel(&[PREFIX_PLAYER_GAMES, player.as_slice()], storage);
+    let history = match AppendStore::<GameId, _>::attach(&history_storage) {
+        Some(history) => history?,
+        None => return Ok(vec![]),
+    };
+
+    // page backwards from the given game or from the end of the history
+    let end = start_after_position.unwrap_or_else(|| history.len());
+    (0..end)
+        .rev()
+        .take(limit as usize)
+        .map(|position| history.get_at(position))
+        .collect()
 }
 
 /// Returns StdResult<()> resulting from saving an item to storage
@@ -213,3 +977,114 @@ pub fn json_may_load<T: DeserializeOwned, S: ReadonlyStorage>(
         None => Ok(None),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Seat;
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::Binary;
+
+    fn game_details(status: GameStatus, players: &[&str]) -> GameDetails {
+        let mut game_details = GameDetails::default();
+        game_details.game.status = status;
+        game_details.game.seats = players
+            .iter()
+            .map(|player| Seat::new(HumanAddr::from(*player), String::new()))
+            .collect();
+        game_details
+    }
+
+    #[test]
+    fn status_and_player_indices_follow_the_saved_games() {
+        let mut storage = MockStorage::new();
+
+        save_game(
+            &mut storage,
+            1,
+            &game_details(GameStatus::Pending, &["alice"]),
+        )
+        .unwrap();
+        save_game(
+            &mut storage,
+            2,
+            &game_details(GameStatus::Pending, &["alice2"]),
+        )
+        .unwrap();
+        save_game(
+            &mut storage,
+            3,
+            &game_details(GameStatus::Pending, &["bob"]),
+        )
+        .unwrap();
+        save_game(
+            &mut storage,
+            3,
+            &game_details(GameStatus::Started, &["bob", "alice"]),
+        )
+        .unwrap();
+        save_game(
+            &mut storage,
+            260,
+            &game_details(GameStatus::Pending, &["carol"]),
+        )
+        .unwrap();
+        remove_game(&mut storage, 2).unwrap();
+
+        let status_games = |status, start_after| -> Vec<GameId> {
+            load_status_games(&storage, &status, start_after)
+                .collect::<StdResult<_>>()
+                .unwrap()
+        };
+        assert_eq!(status_games(GameStatus::Pending, None), vec![1, 260]);
+        assert_eq!(status_games(GameStatus::Pending, Some(1)), vec![260]);
+        // the page following a game, which left the index, is empty
+        assert_eq!(
+            status_games(GameStatus::Pending, Some(3)),
+            Vec::<GameId>::new()
+        );
+        assert_eq!(status_games(GameStatus::Started, None), vec![3]);
+        assert_eq!(
+            status_games(GameStatus::Finished, None),
+            Vec::<GameId>::new()
+        );
+
+        let player_games = |player: &str, start_after| -> Vec<GameId> {
+            load_player_active_games(&storage, &HumanAddr::from(player), start_after)
+                .collect::<StdResult<_>>()
+                .unwrap()
+        };
+        assert_eq!(player_games("alice", None), vec![1, 3]);
+        assert_eq!(player_games("alice", Some(1)), vec![3]);
+        assert_eq!(player_games("alice2", None), Vec::<GameId>::new());
+        assert_eq!(player_games("bob", None), vec![3]);
+    }
+
+    #[test]
+    fn player_games_are_paged_from_the_latest_one() {
+        let mut storage = MockStorage::new();
+        let alice = CanonicalAddr(Binary(vec![1; 20]));
+        let bob = CanonicalAddr(Binary(vec![2; 20]));
+
+        for game_id in [1, 4, 256, 300].iter() {
+            save_player_game(&mut storage, &alice, *game_id).unwrap();
+        }
+        save_player_game(&mut storage, &bob, 2).unwrap();
+
+        let page = |start_after| -> Vec<GameId> {
+            load_player_games(&storage, &alice, start_after, 2).unwrap()
+        };
+        assert_eq!(page(None), vec![300, 256]);
+        assert_eq!(page(Some(256)), vec![4, 1]);
+        assert_eq!(page(Some(1)), Vec::<GameId>::new());
+        assert_eq!(page(Some(2)), Vec::<GameId>::new());
+        assert_eq!(
+            load_player_games(&storage, &bob, None, 10).unwrap(),
+            vec![2]
+        );
+        assert_eq!(
+            load_player_games(&storage, &CanonicalAddr(Binary(vec![3; 20])), None, 10).unwrap(),
+            Vec::<GameId>::new()
+        );
+    }
+}
@@ -0,0 +1,472 @@
+use crate::contract::GameId;
+use crate::error::{ContractError, ContractResult};
+use crate::game::{basis_points_share, Player, SecretHash, BASIS_POINTS};
+use cosmwasm_std::{coin, BlockInfo, Coin, HumanAddr, StdError};
+use rand::seq::SliceRandom;
+use rand_chacha::ChaChaRng;
+use rand_core::SeedableRng;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub type TournamentId = u64;
+
+// Min and max num of players in the single-elimination bracket, should be a power of two
+pub const MIN_BRACKET_SIZE: u8 = 4;
+pub const MAX_BRACKET_SIZE: u8 = 64;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentStatus {
+    // players can register, until the bracket is full
+    Registration,
+    // bracket matches are played
+    Started,
+    // final is played and prizes are paid out
+    Finished,
+    // cancelled by the organiser before it started, entry fees are refunded
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TournamentPlayer {
+    pub address: HumanAddr,
+    pub nft_id: String,
+}
+
+/// Bracket match, the game is spawned for, once both players are known
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct BracketMatch {
+    // indices of the tournament players, taking the game seats in the same order
+    pub players: Vec<usize>,
+    pub game_id: Option<GameId>,
+    // index of the tournament player, advancing to the next round
+    pub winner: Option<usize>,
+}
+
+/// Bracket match, the game is played for
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TournamentMatch {
+    pub tournament_id: TournamentId,
+    // bracket round, 0 stands for the first one
+    pub round: u8,
+    // index of the match in the round
+    pub index: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Tournament {
+    pub organiser: HumanAddr,
+    pub status: TournamentStatus,
+    // num of players in the bracket
+    pub bracket_size: u8,
+    pub entry_fee: Coin,
+    // min xp of the dice NFT, player registers with
+    pub min_xp: u32,
+    // prize shares in basis points by the final standing: champion, runner-up,
+    // each of the semifinal losers, each of the quarterfinal losers and so on
+    pub prize_split: Vec<u16>,
+    // rake in basis points, taken from the prize pool to the DAO treasury
+    pub rake: u16,
+    // registered players, in the order of registration
+    pub players: Vec<TournamentPlayer>,
+    // bracket matches by rounds, the last round is the final
+    pub rounds: Vec<Vec<BracketMatch>>,
+    // prizes paid out to the players, indexed as them, once the tournament is finished
+    pub prizes: Vec<Coin>,
+    // block height, the tournament was created at
+    pub created_height: u64,
+}
+
+impl Tournament {
+    pub fn new(
+        organiser: HumanAddr,
+        bracket_size: u8,
+        entry_fee: Coin,
+        min_xp: u32,
+        prize_split: Vec<u16>,
+        rake: u16,
+        block: &BlockInfo,
+    ) -> Self {
+        let rounds = (0..num_of_rounds(bracket_size))
+            .map(|round| vec![BracketMatch::default(); (bracket_size >> (round + 1)) as usize])
+            .collect();
+
+        Self {
+            organiser,
+            status: TournamentStatus::Registration,
+            bracket_size,
+            entry_fee,
+            min_xp,
+            prize_split,
+            rake,
+            players: vec![],
+            rounds,
+            prizes: vec![],
+            created_height: block.height,
+        }
+    }
+
+    /// Check whether given account registered for the tournament
+    pub fn is_registered(&self, address: &HumanAddr) -> bool {
+        self.players.iter().any(|player| player.address == *address)
+    }
+
+    /// Check whether all the bracket places are taken
+    pub fn is_full(&self) -> bool {
+        self.players.len() == self.bracket_size as usize
+    }
+
+    /// Get the total amount of the entry fees paid
+    pub fn prize_pool(&self) -> Coin {
+        coin(
+            self.entry_fee.amount.u128() * self.players.len() as u128,
+            &self.entry_fee.denom,
+        )
+    }
+
+    /// Record winner (by the game seat) of the bracket match
+    /// Returns the next round match, if both of it's players are known now
+    /// Tournament is finished, once the final winner is recorded
+    pub fn record_winner(&mut self, round: u8, index: usize, winner: Player) -> Option<usize> {
+        let bracket_match = &mut self.rounds[round as usize][index];
+        let winner = bracket_match.players[winner];
+        bracket_match.winner = Some(winner);
+
+        let next_round = round as usize + 1;
+        if next_round == self.rounds.len() {
+            self.status = TournamentStatus::Finished;
+            return None;
+        }
+
+        // winners of the adjacent matches play each other in the next round
+        let next_index = index / 2;
+        let next_match = &mut self.rounds[next_round][next_index];
+        match index % 2 {
+            0 => next_match.players.insert(0, winner),
+            _ => next_match.players.push(winner),
+        }
+
+        if next_match.players.len() == 2 {
+            Some(next_index)
+        } else {
+            None
+        }
+    }
+
+    /// Get final standing of each player, 0 stands for the champion, 1 for the runner-up,
+    /// 2 for the semifinal losers and so on
+    pub fn standings(&self) -> Vec<usize> {
+        let num_of_rounds = self.rounds.len();
+        let mut standings = vec![num_of_rounds; self.players.len()];
+
+        for (round, matches) in self.rounds.iter().enumerate() {
+            for bracket_match in matches {
+                for player in bracket_match.players.iter() {
+                    if bracket_match.winner != Some(*player) {
+                        standings[*player] = num_of_rounds - round;
+                    } else if round + 1 == num_of_rounds {
+                        standings[*player] = 0;
+                    }
+                }
+            }
+        }
+
+        standings
+    }
+
+    /// Get prizes of the players, indexed as them, and the rake taken from the prize pool
+    /// Remainder of the prize pool goes to the champion
+    pub fn payouts(&self) -> (Vec<u128>, u128) {
+        let prize_pool = self.prize_pool().amount.u128();
+        let rake = basis_points_share(prize_pool, self.rake);
+
+        let standings = self.standings();
+        let mut prizes: Vec<u128> = standings
+            .iter()
+            .map(|standing| {
+                basis_points_share(
+                    prize_pool - rake,
+                    self.prize_split.get(*standing).copied().unwrap_or_default(),
+                )
+            })
+            .collect();
+
+        let remainder = prize_pool - rake - prizes.iter().sum::<u128>();
+        if let Some(champion) = standings.iter().position(|standing| *standing == 0) {
+            prizes[champion] += remainder;
+        }
+
+        (prizes, rake)
+    }
+
+    /// Ensure players can register for the tournament
+    pub fn ensure_is_in_registration(&self) -> ContractResult<()> {
+        if self.status != TournamentStatus::Registration {
+            Err(StdError::generic_err(
+                ContractError::TournamentNotInRegistration {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ensure given account did not register for the tournament yet
+    pub fn ensure_is_not_registered(&self, address: &HumanAddr) -> ContractResult<()> {
+        if self.is_registered(address) {
+            Err(StdError::generic_err(
+                ContractError::AlreadyRegistered {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ensure given account is the tournament organiser
+    pub fn ensure_is_organiser(&self, address: &HumanAddr) -> ContractResult<()> {
+        if self.organiser != *address {
+            Err(StdError::generic_err(
+                ContractError::NotAnOrganiser {}.to_string(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Get num of the bracket rounds, final included
+pub fn num_of_rounds(bracket_size: u8) -> u8 {
+    bracket_size.trailing_zeros() as u8
+}
+
+/// Ensure tournament can be played with the given bracket size, entry fee and prize split
+/// Prize shares of all the standings should add up to the whole prize pool
+pub fn ensure_correct_tournament(
+    bracket_size: u8,
+    entry_fee: &Coin,
+    prize_split: &[u16],
+) -> ContractResult<()> {
+    let correct_bracket_size = bracket_size.is_power_of_two()
+        && (MIN_BRACKET_SIZE..=MAX_BRACKET_SIZE).contains(&bracket_size);
+
+    // num of the players, sharing each standing: 1 champion, 1 runner-up, 2 semifinal losers...
+    let total_split: u64 = prize_split
+        .iter()
+        .enumerate()
+        .map(|(standing, share)| {
+            let num_of_players = if standing == 0 {
+                1
+            } else {
+                1u64 << (standing - 1)
+            };
+            *share as u64 * num_of_players
+        })
+        .sum();
+    let correct_prize_split = prize_split.len() <= num_of_rounds(bracket_size) as usize + 1
+        && total_split == BASIS_POINTS as u64;
+
+    if correct_bracket_size && correct_prize_split && !entry_fee.amount.is_zero() {
+        Ok(())
+    } else {
+        Err(StdError::generic_err(
+            ContractError::InvalidTournament {}.to_string(),
+        ))
+    }
+}
+
+/// Tournament with the secrets, registered players committed to
+#[derive(Serialize, Deserialize, Debug, PartialEq, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct TournamentDetails {
+    pub tournament: Tournament,
+    // hashes of the secrets, indexed as the players, revealed in each bracket match game
+    pub secrets: Vec<SecretHash>,
+}
+
+impl TournamentDetails {
+    /// Register player for the tournament
+    pub fn register(&mut self, address: HumanAddr, nft_id: String, secret_hash: SecretHash) {
+        self.tournament
+            .players
+            .push(TournamentPlayer { address, nft_id });
+        self.secrets.push(secret_hash);
+    }
+
+    /// Withdraw registration of the player, along with the secret they committed to
+    pub fn unregister(&mut self, address: &HumanAddr) -> ContractResult<()> {
+        let player = self
+            .tournament
+            .players
+            .iter()
+            .position(|player| player.address == *address)
+            .ok_or_else(|| StdError::generic_err(ContractError::NotRegistered {}.to_string()))?;
+
+        self.tournament.players.remove(player);
+        self.secrets.remove(player);
+
+        Ok(())
+    }
+
+    /// Start the tournament, pairing the players of the first round in random order
+    /// Seed combines all the commitments and the block data with the contract entropy pool,
+    /// which players can't read, so the last one to register can't grind their commitment
+    pub fn start(&mut self, block: &BlockInfo, entropy: &[u8]) {
+        let mut combined_secret = vec![];
+        for secret_hash in self.secrets.iter() {
+            combined_secret.extend(secret_hash);
+        }
+        combined_secret.extend(&block.height.to_be_bytes());
+        combined_secret.extend(&block.time.to_be_bytes());
+        combined_secret.extend(block.chain_id.as_bytes());
+        combined_secret.extend(entropy);
+
+        let seed: [u8; 32] = Sha256::digest(&combined_secret).into();
+        let mut rng = ChaChaRng::from_seed(seed);
+
+        let mut players: Vec<usize> = (0..self.tournament.players.len()).collect();
+        players.shuffle(&mut rng);
+
+        for (bracket_match, pair) in self.tournament.rounds[0].iter_mut().zip(players.chunks(2)) {
+            bracket_match.players = pair.to_vec();
+        }
+
+        self.tournament.status = TournamentStatus::Started;
+    }
+}
+
+impl From<TournamentDetails> for Tournament {
+    fn from(tournament_details: TournamentDetails) -> Self {
+        tournament_details.tournament
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // tournament with the bracket full, players are paired in the order of registration
+    fn full_tournament(bracket_size: u8, prize_split: Vec<u16>, rake: u16) -> TournamentDetails {
+        let block = BlockInfo {
+            height: 1,
+            time: 1,
+            chain_id: "secret-testnet".to_string(),
+        };
+        let tournament = Tournament::new(
+            HumanAddr::from("organiser"),
+            bracket_size,
+            coin(100, "uscrt"),
+            0,
+            prize_split,
+            rake,
+            &block,
+        );
+        let mut tournament_details = TournamentDetails {
+            tournament,
+            secrets: vec![],
+        };
+
+        for player in 0..bracket_size {
+            tournament_details.register(
+                HumanAddr::from(format!("player{}", player)),
+                format!("nft{}", player),
+                SecretHash::default(),
+            );
+        }
+        for (index, bracket_match) in tournament_details.tournament.rounds[0]
+            .iter_mut()
+            .enumerate()
+        {
+            bracket_match.players = vec![2 * index, 2 * index + 1];
+        }
+        tournament_details.tournament.status = TournamentStatus::Started;
+
+        tournament_details
+    }
+
+    #[test]
+    fn standings_of_4_players_bracket() {
+        let mut tournament = full_tournament(4, vec![10_000], 0).tournament;
+
+        assert_eq!(tournament.record_winner(0, 0, 1), None);
+        assert_eq!(tournament.record_winner(0, 1, 0), Some(0));
+        assert_eq!(tournament.rounds[1][0].players, vec![1, 2]);
+        assert_eq!(tournament.record_winner(1, 0, 1), None);
+
+        assert_eq!(tournament.status, TournamentStatus::Finished);
+        assert_eq!(tournament.standings(), vec![2, 1, 0, 2]);
+    }
+
+    #[test]
+    fn standings_of_8_players_bracket() {
+        let mut tournament = full_tournament(8, vec![10_000], 0).tournament;
+
+        for index in 0..4 {
+            tournament.record_winner(0, index, 0);
+        }
+        tournament.record_winner(1, 0, 1);
+        tournament.record_winner(1, 1, 1);
+        assert_eq!(tournament.rounds[2][0].players, vec![2, 6]);
+        tournament.record_winner(2, 0, 0);
+
+        assert_eq!(tournament.status, TournamentStatus::Finished);
+        assert_eq!(tournament.standings(), vec![2, 3, 0, 3, 2, 3, 1, 3]);
+    }
+
+    #[test]
+    fn payouts_follow_the_prize_split_and_the_remainder_goes_to_the_champion() {
+        let mut tournament = full_tournament(4, vec![6000, 2500, 750], 500).tournament;
+        tournament.record_winner(0, 0, 1);
+        tournament.record_winner(0, 1, 0);
+        tournament.record_winner(1, 0, 1);
+
+        // 400 prize pool less 20 rake: 228 + 1 remainder, 95 and 28 for each semifinal loser
+        assert_eq!(tournament.payouts(), (vec![28, 95, 229, 28], 20));
+    }
+
+    #[test]
+    fn prize_split_should_cover_the_whole_prize_pool() {
+        let entry_fee = coin(100, "uscrt");
+
+        assert!(ensure_correct_tournament(4, &entry_fee, &[10_000]).is_ok());
+        assert!(ensure_correct_tournament(4, &entry_fee, &[6000, 2500, 750]).is_ok());
+        assert!(ensure_correct_tournament(8, &entry_fee, &[5000, 2000, 1000, 250]).is_ok());
+
+        // shares do not add up to the whole prize pool
+        assert!(ensure_correct_tournament(4, &entry_fee, &[6000, 2500, 1500]).is_err());
+        assert!(ensure_correct_tournament(4, &entry_fee, &[9000]).is_err());
+        // more standings, than the bracket has
+        assert!(ensure_correct_tournament(4, &entry_fee, &[5000, 2000, 1000, 250]).is_err());
+    }
+
+    #[test]
+    fn bracket_size_should_be_a_power_of_two_in_range() {
+        let entry_fee = coin(100, "uscrt");
+
+        assert!(ensure_correct_tournament(6, &entry_fee, &[10_000]).is_err());
+        assert!(ensure_correct_tournament(2, &entry_fee, &[10_000]).is_err());
+        assert!(ensure_correct_tournament(128, &entry_fee, &[10_000]).is_err());
+        assert!(ensure_correct_tournament(64, &entry_fee, &[10_000]).is_ok());
+        assert!(ensure_correct_tournament(4, &coin(0, "uscrt"), &[10_000]).is_err());
+    }
+
+    #[test]
+    fn unregister_removes_the_player_with_their_secret() {
+        let mut tournament_details = full_tournament(4, vec![10_000], 0);
+        tournament_details.secrets[2] = [2; 32];
+
+        tournament_details
+            .unregister(&HumanAddr::from("player1"))
+            .unwrap();
+
+        assert_eq!(tournament_details.tournament.players.len(), 3);
+        assert_eq!(tournament_details.tournament.players[1].nft_id, "nft2");
+        assert_eq!(tournament_details.secrets[1], [2; 32]);
+        assert!(tournament_details
+            .unregister(&HumanAddr::from("player1"))
+            .is_err());
+    }
+}